@@ -0,0 +1,39 @@
+use serde_json::{Map, Value};
+
+pub const SMALL: &[u8] = include_bytes!("../fixtures/small.json");
+pub const PETSTORE: &[u8] = include_bytes!("../fixtures/petstore.json");
+pub const MEDIUM: &[u8] = include_bytes!("../fixtures/medium.json");
+
+/// Copies the paths and definitions of the medium document 100 times, renaming them so that each copy refers to its own definitions.
+pub fn large() -> Vec<u8> {
+    let medium: Value = serde_json::from_slice(MEDIUM).unwrap();
+    let mut paths = Map::new();
+    let mut definitions = Map::new();
+    for copy in 0..100 {
+        let text = serde_json::to_string(&medium)
+            .unwrap()
+            .replace("#/definitions/", &format!("#/definitions/Copy{}", copy))
+            .replace("\"operationId\":\"", &format!("\"operationId\":\"Copy{}", copy));
+        let spec: Value = serde_json::from_str(&text).unwrap();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            paths.insert(format!("/copy{}{}", copy, path), item.clone());
+        }
+        for (name, schema) in spec["definitions"].as_object().unwrap() {
+            definitions.insert(format!("Copy{}{}", copy, name), schema.clone());
+        }
+    }
+    let mut spec = medium;
+    spec["paths"] = Value::Object(paths);
+    spec["definitions"] = Value::Object(definitions);
+    serde_json::to_vec(&spec).unwrap()
+}
+
+/// The fixtures by name, from small to very large.
+pub fn fixtures() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("small", SMALL.to_vec()),
+        ("petstore", PETSTORE.to_vec()),
+        ("medium", MEDIUM.to_vec()),
+        ("large", large()),
+    ]
+}
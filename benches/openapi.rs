@@ -0,0 +1,117 @@
+// cargo bench --bench openapi
+// Measures deserializing, serializing, resolving references and validating the fixtures of different sizes,
+// and compares deserializing the large fixture into the owned types with parsing its sections on demand,
+// into either the owned or the borrowed types.
+
+mod common;
+
+use autorust_openapi::{BorrowedOpenAPI, OpenAPI, ReferenceOr};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn deserialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deserialize");
+    for (name, bytes) in common::fixtures() {
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &bytes, |b, bytes| {
+            b.iter(|| serde_json::from_slice::<OpenAPI>(black_box(bytes)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize");
+    for (name, bytes) in common::fixtures() {
+        let spec: OpenAPI = serde_json::from_slice(&bytes).unwrap();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &spec, |b, spec| {
+            b.iter(|| serde_json::to_vec(black_box(spec)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Resolves the properties and `allOf` schemas of every definition, as a code generator would.
+fn resolve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve");
+    for (name, bytes) in common::fixtures() {
+        let spec: OpenAPI = serde_json::from_slice(&bytes).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &spec, |b, spec| {
+            b.iter(|| {
+                let mut resolved = 0;
+                for schema in spec.definitions.values() {
+                    if let ReferenceOr::Item(schema) = schema {
+                        for child in schema.properties.values().chain(schema.all_of.iter()) {
+                            if spec.resolve_schema(child).is_some() {
+                                resolved += 1;
+                            }
+                        }
+                    }
+                }
+                resolved
+            })
+        });
+    }
+    group.finish();
+}
+
+fn validate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate");
+    for (name, bytes) in common::fixtures() {
+        let spec: OpenAPI = serde_json::from_slice(&bytes).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &spec, |b, spec| b.iter(|| spec.validate()));
+    }
+    group.finish();
+}
+
+fn borrowed(c: &mut Criterion) {
+    let bytes = common::large();
+    let mut group = c.benchmark_group("borrowed");
+    group.bench_function("owned", |b| {
+        b.iter(|| serde_json::from_slice::<OpenAPI>(black_box(&bytes)).unwrap())
+    });
+    group.bench_function("borrowed", |b| b.iter(|| BorrowedOpenAPI::from_slice(black_box(&bytes)).unwrap()));
+    group.bench_function("borrowed one definition", |b| {
+        b.iter(|| {
+            let spec = BorrowedOpenAPI::from_slice(black_box(&bytes)).unwrap();
+            spec.definition("Copy50Widget").unwrap().unwrap()
+        })
+    });
+    let spec = BorrowedOpenAPI::from_slice(&bytes).unwrap();
+    group.bench_function("owned definitions", |b| {
+        b.iter(|| {
+            spec.definitions
+                .keys()
+                .map(|name| spec.definition(name).unwrap().unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("borrowed definitions", |b| {
+        b.iter(|| {
+            spec.definitions
+                .keys()
+                .map(|name| spec.borrowed_definition(name).unwrap().unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("owned paths", |b| {
+        b.iter(|| {
+            spec.paths
+                .keys()
+                .map(|path| spec.path_item(path).unwrap().unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("borrowed paths", |b| {
+        b.iter(|| {
+            spec.paths
+                .keys()
+                .map(|path| spec.borrowed_path_item(path).unwrap().unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, deserialize, serialize, resolve, validate, borrowed);
+criterion_main!(benches);
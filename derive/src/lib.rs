@@ -0,0 +1,549 @@
+//! `#[derive(OpenApiSchema)]` for autorust_openapi
+//! The schema follows how serde serializes the type, honoring `rename`, `rename_all`, `skip`, `skip_serializing`,
+//! `skip_serializing_if`, `default`, `flatten`, `transparent` and internally tagged enums.
+//! Doc comments become descriptions.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Group, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{
+    ext::IdentExt, meta::ParseNestedMeta, parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields,
+    Lit, LitStr, Meta, Result, Token, Type, Variant,
+};
+
+#[proc_macro_derive(OpenApiSchema, attributes(serde))]
+pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    if let Some(param) = input.generics.type_params().next() {
+        return Err(Error::new_spanned(param, "OpenApiSchema can not be derived for generic types"));
+    }
+    let attrs = SerdeAttrs::parse(&input.attrs)?;
+    let ident = &input.ident;
+    let name = attrs.rename.clone().unwrap_or_else(|| ident.unraw().to_string());
+    let description = describe(quote!(schema.common.description), &input.attrs);
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => return Ok(transparent(input, &fields.unnamed[0].ty)),
+            Fields::Named(fields) if attrs.transparent => {
+                for field in &fields.named {
+                    if !is_skipped(&field.attrs)? {
+                        return Ok(transparent(input, &field.ty));
+                    }
+                }
+                return Err(Error::new_spanned(ident, "a transparent struct needs a field"));
+            }
+            Fields::Named(_) => {
+                let fields = object_fields(&data.fields, attrs.rename_all, attrs.default)?;
+                quote! {
+                    let mut schema = ::autorust_openapi::Schema::object();
+                    #fields
+                    #description
+                    schema
+                }
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    ident,
+                    "OpenApiSchema needs a struct with named fields or a newtype struct",
+                ))
+            }
+        },
+        Data::Enum(data) => {
+            let mut variants = Vec::new();
+            for variant in &data.variants {
+                if !is_skipped(&variant.attrs)? {
+                    variants.push(variant);
+                }
+            }
+            if attrs.untagged || attrs.content.is_some() {
+                return Err(Error::new_spanned(
+                    ident,
+                    "OpenApiSchema only supports externally and internally tagged enums",
+                ));
+            }
+            let enum_schema = match &attrs.tag {
+                Some(tag) => internally_tagged(&name, tag, &variants, attrs.rename_all)?,
+                None if variants.iter().all(|variant| matches!(variant.fields, Fields::Unit)) => {
+                    unit_enum(&name, &variants, attrs.rename_all)?
+                }
+                None => externally_tagged(&variants, attrs.rename_all)?,
+            };
+            quote! {
+                let mut schema = { #enum_schema };
+                #description
+                schema
+            }
+        }
+        Data::Union(_) => return Err(Error::new_spanned(ident, "OpenApiSchema can not be derived for unions")),
+    };
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::autorust_openapi::OpenApiSchema for #ident #type_generics #where_clause {
+            fn definition_name() -> ::std::option::Option<::std::string::String> {
+                ::std::option::Option::Some(#name.to_owned())
+            }
+
+            #[allow(unused_mut)]
+            fn schema(definitions: &mut ::autorust_openapi::Definitions) -> ::autorust_openapi::Schema {
+                #body
+            }
+        }
+    })
+}
+
+/// Newtype and transparent structs are described by the type they wrap.
+fn transparent(input: &DeriveInput, ty: &Type) -> TokenStream2 {
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::autorust_openapi::OpenApiSchema for #ident #type_generics #where_clause {
+            fn definition_name() -> ::std::option::Option<::std::string::String> {
+                <#ty as ::autorust_openapi::OpenApiSchema>::definition_name()
+            }
+
+            fn schema(definitions: &mut ::autorust_openapi::Definitions) -> ::autorust_openapi::Schema {
+                <#ty as ::autorust_openapi::OpenApiSchema>::schema(definitions)
+            }
+
+            fn schema_ref(
+                definitions: &mut ::autorust_openapi::Definitions,
+            ) -> ::autorust_openapi::ReferenceOr<::autorust_openapi::Schema> {
+                <#ty as ::autorust_openapi::OpenApiSchema>::schema_ref(definitions)
+            }
+
+            fn optional() -> bool {
+                <#ty as ::autorust_openapi::OpenApiSchema>::optional()
+            }
+        }
+    }
+}
+
+/// Adds the fields to `schema` as properties.
+fn object_fields(fields: &Fields, rename_all: Option<RenameRule>, container_default: bool) -> Result<TokenStream2> {
+    let mut tokens = TokenStream2::new();
+    for field in fields {
+        let attrs = SerdeAttrs::parse(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let ty = &field.ty;
+        if attrs.flatten {
+            tokens.extend(quote! {
+                {
+                    let flattened = <#ty as ::autorust_openapi::OpenApiSchema>::schema(definitions);
+                    schema.properties.extend(flattened.properties);
+                    if !<#ty as ::autorust_openapi::OpenApiSchema>::optional() {
+                        for name in flattened.required {
+                            schema = schema.required(name);
+                        }
+                    }
+                    if flattened.additional_properties.is_some() {
+                        schema.additional_properties = flattened.additional_properties;
+                    }
+                }
+            });
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let name = attrs.rename.unwrap_or_else(|| {
+            rename_all.map_or_else(|| ident.unraw().to_string(), |rule| rule.apply_to_field(&ident.unraw().to_string()))
+        });
+        let description = property_description(&field.attrs);
+        // without `skip_serializing_if`, a `None` is serialized as `null`
+        let nullable = if attrs.skip_serializing_if {
+            quote!()
+        } else {
+            quote! {
+                if <#ty as ::autorust_openapi::OpenApiSchema>::optional() {
+                    property = match property {
+                        ::autorust_openapi::ReferenceOr::Item(schema) => ::autorust_openapi::ReferenceOr::Item(schema.nullable()),
+                        reference => ::autorust_openapi::ReferenceOr::Item(::autorust_openapi::Schema::default().all_of(reference).nullable()),
+                    };
+                }
+            }
+        };
+        let required = if attrs.skip_serializing_if || attrs.default || container_default {
+            quote!()
+        } else {
+            quote! {
+                if !<#ty as ::autorust_openapi::OpenApiSchema>::optional() {
+                    schema = schema.required(#name);
+                }
+            }
+        };
+        tokens.extend(quote! {
+            {
+                let mut property = <#ty as ::autorust_openapi::OpenApiSchema>::schema_ref(definitions);
+                #nullable
+                #description
+                schema = schema.property(#name, property);
+                #required
+            }
+        });
+    }
+    Ok(tokens)
+}
+
+/// Sets the description of `property`, whether it is a reference or an inline schema.
+fn property_description(attrs: &[Attribute]) -> TokenStream2 {
+    match doc(attrs) {
+        Some(doc) => quote! {
+            match &mut property {
+                ::autorust_openapi::ReferenceOr::Item(schema) => schema.common.description = ::std::option::Option::Some(#doc.to_owned()),
+                ::autorust_openapi::ReferenceOr::Reference { description, .. } => {
+                    *description = ::std::option::Option::Some(#doc.to_owned())
+                }
+            }
+        },
+        None => quote!(),
+    }
+}
+
+fn describe(target: TokenStream2, attrs: &[Attribute]) -> TokenStream2 {
+    match doc(attrs) {
+        Some(doc) => quote!(#target = ::std::option::Option::Some(#doc.to_owned());),
+        None => quote!(),
+    }
+}
+
+fn variant_name(variant: &Variant, rename_all: Option<RenameRule>) -> Result<String> {
+    let attrs = SerdeAttrs::parse(&variant.attrs)?;
+    let ident = variant.ident.unraw().to_string();
+    Ok(attrs
+        .rename
+        .unwrap_or_else(|| rename_all.map_or_else(|| ident.clone(), |rule| rule.apply_to_variant(&ident))))
+}
+
+/// An enum of unit variants is a string with `enum` values and `x-ms-enum` metadata.
+fn unit_enum(name: &str, variants: &[&Variant], rename_all: Option<RenameRule>) -> Result<TokenStream2> {
+    let mut enum_values = Vec::new();
+    let mut values = Vec::new();
+    for variant in variants {
+        let value = variant_name(variant, rename_all)?;
+        let description = match doc(&variant.attrs) {
+            Some(doc) => quote!(::std::option::Option::Some(#doc.to_owned())),
+            None => quote!(::std::option::Option::None),
+        };
+        values.push(quote! {
+            ::autorust_openapi::MsEnumValue {
+                value: #value.into(),
+                description: #description,
+                name: ::std::option::Option::None,
+            }
+        });
+        enum_values.push(value);
+    }
+    Ok(quote! {
+        let mut schema = ::autorust_openapi::Schema::string() #(.enum_value(#enum_values))*;
+        schema.common.x_ms_enum = ::std::option::Option::Some(::autorust_openapi::MsEnum {
+            name: #name.to_owned(),
+            model_as_string: ::std::option::Option::Some(false),
+            values: ::std::vec![#(#values),*],
+        });
+        schema
+    })
+}
+
+/// An externally tagged enum with data is an object with a property for each variant, of which one is set.
+fn externally_tagged(variants: &[&Variant], rename_all: Option<RenameRule>) -> Result<TokenStream2> {
+    let mut tokens = quote!(let mut schema = ::autorust_openapi::Schema::object(););
+    for variant in variants {
+        let name = variant_name(variant, rename_all)?;
+        let variant_rename_all = SerdeAttrs::parse(&variant.attrs)?.rename_all;
+        let description = property_description(&variant.attrs);
+        let property = match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                quote!(<#ty as ::autorust_openapi::OpenApiSchema>::schema_ref(definitions))
+            }
+            Fields::Named(_) => {
+                let fields = object_fields(&variant.fields, variant_rename_all, false)?;
+                quote! {
+                    {
+                        let mut schema = ::autorust_openapi::Schema::object();
+                        #fields
+                        ::autorust_openapi::ReferenceOr::Item(schema)
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "OpenApiSchema can not mix unit and tuple variants with data variants unless the enum is internally tagged",
+                ))
+            }
+        };
+        tokens.extend(quote! {
+            {
+                let mut property = #property;
+                #description
+                schema = schema.property(#name, property);
+            }
+        });
+    }
+    tokens.extend(quote!(schema));
+    Ok(tokens)
+}
+
+/// An internally tagged enum is a polymorphic base definition with a `discriminator`.
+/// Each variant is added as a definition named `{Enum}{Variant}`, which has the base in `allOf` and the tag as `x-ms-discriminator-value`.
+/// A newtype variant also has the type it wraps in `allOf`.
+/// Describing the enum panics if another type already has a different definition by the name of a variant.
+fn internally_tagged(name: &str, tag: &str, variants: &[&Variant], rename_all: Option<RenameRule>) -> Result<TokenStream2> {
+    let mut tokens = quote! {
+        let schema = ::autorust_openapi::Schema::object()
+            .property(#tag, ::autorust_openapi::Schema::string())
+            .required(#tag)
+            .discriminator(#tag);
+    };
+    for variant in variants {
+        let value = variant_name(variant, rename_all)?;
+        let variant_rename_all = SerdeAttrs::parse(&variant.attrs)?.rename_all;
+        let variant_ident = variant.ident.unraw();
+        let definition = format!("{}{}", name, variant_ident);
+        let child = match &variant.fields {
+            Fields::Unit => quote!(::autorust_openapi::Schema::object().all_of(::autorust_openapi::Schema::reference(#name))),
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                quote! {
+                    ::autorust_openapi::Schema::object()
+                        .all_of(::autorust_openapi::Schema::reference(#name))
+                        .all_of(<#ty as ::autorust_openapi::OpenApiSchema>::schema_ref(definitions))
+                }
+            }
+            Fields::Named(_) => {
+                let fields = object_fields(&variant.fields, variant_rename_all, false)?;
+                quote! {
+                    {
+                        let mut schema = ::autorust_openapi::Schema::object();
+                        #fields
+                        schema.all_of(::autorust_openapi::Schema::reference(#name))
+                    }
+                }
+            }
+            Fields::Unnamed(fields) => return Err(Error::new(fields.span(), "internally tagged tuple variants can not be serialized")),
+        };
+        let variant_path = format!("{}::{}", name, variant_ident);
+        let description = describe(quote!(schema.common.description), &variant.attrs);
+        tokens.extend(quote! {
+            {
+                let mut schema = #child.discriminator_value(#value);
+                #description
+                let child = ::autorust_openapi::ReferenceOr::Item(schema);
+                match definitions.get(#definition) {
+                    ::std::option::Option::Some(existing) if existing != &child => {
+                        ::std::panic!("the definition {} of {} is already used by another type", #definition, #variant_path)
+                    }
+                    _ => {
+                        definitions.insert(#definition.to_owned(), child);
+                    }
+                }
+            }
+        });
+    }
+    tokens.extend(quote!(schema));
+    Ok(tokens)
+}
+
+fn is_skipped(attrs: &[Attribute]) -> Result<bool> {
+    Ok(SerdeAttrs::parse(attrs)?.skip)
+}
+
+/// Joins the lines of the doc comments.
+fn doc(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).trim_end().to_owned())
+        .collect();
+    let doc = lines.join("\n").trim().to_owned();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc)
+    }
+}
+
+/// The serde attributes that change the schema. Others are ignored.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<RenameRule>,
+    tag: Option<String>,
+    content: Option<String>,
+    untagged: bool,
+    transparent: bool,
+    skip: bool,
+    skip_serializing_if: bool,
+    default: bool,
+    flatten: bool,
+}
+
+impl SerdeAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut parsed = SerdeAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if let Some(name) = serialize_value(&meta)? {
+                        parsed.rename = Some(name.value());
+                    }
+                } else if meta.path.is_ident("rename_all") {
+                    if let Some(rule) = serialize_value(&meta)? {
+                        parsed.rename_all = Some(RenameRule::parse(&rule)?);
+                    }
+                } else if meta.path.is_ident("tag") {
+                    parsed.tag = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("content") {
+                    parsed.content = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("untagged") {
+                    parsed.untagged = true;
+                } else if meta.path.is_ident("transparent") {
+                    parsed.transparent = true;
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    parsed.skip = true;
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    parsed.skip_serializing_if = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("default") {
+                    parsed.default = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("flatten") {
+                    parsed.flatten = true;
+                } else {
+                    skip_value(&meta)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+/// Reads `name = "value"` or the `serialize` value of `name(serialize = "value", deserialize = "other")`.
+fn serialize_value(meta: &ParseNestedMeta) -> Result<Option<LitStr>> {
+    if meta.input.peek(Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+    let mut value = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("serialize") {
+            value = Some(inner.value()?.parse()?);
+        } else {
+            skip_value(&inner)?;
+        }
+        Ok(())
+    })?;
+    Ok(value)
+}
+
+fn skip_value(meta: &ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        let input = meta.value()?;
+        while !input.is_empty() && !input.peek(Token![,]) {
+            input.parse::<TokenTree>()?;
+        }
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.input.parse::<Group>()?;
+    }
+    Ok(())
+}
+
+/// https://serde.rs/container-attrs.html#rename_all
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &LitStr) -> Result<Self> {
+        Ok(match rule.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(Error::new_spanned(rule, "unknown rename rule")),
+        })
+    }
+
+    /// Renames a PascalCase variant.
+    fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => lowercase_first(variant),
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake.apply_to_variant(variant).to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake.apply_to_variant(variant).replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake.apply_to_variant(variant).replace('_', "-"),
+        }
+    }
+
+    /// Renames a snake_case field.
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => lowercase_first(&RenameRule::Pascal.apply_to_field(field)),
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+fn lowercase_first(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
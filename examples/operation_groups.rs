@@ -0,0 +1,35 @@
+// Print the operations by operation group, as AutoRest lays out the clients
+// cargo run --example operation_groups -- ../azure-rest-api-specs/specification/vmware/resource-manager/Microsoft.AVS/stable/2020-03-20/vmware.json
+
+use autorust_openapi::*;
+use std::{
+    fs::{self},
+    path::Path,
+    process::exit,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    match std::env::args().nth(1) {
+        None => {
+            eprintln!("Please pass in the spec path.");
+            exit(1);
+        }
+        Some(file_in) => {
+            let file_in = Path::new(&file_in);
+            let bytes = fs::read(file_in)?;
+            let api: OpenAPI = serde_json::from_slice(&bytes)?;
+
+            let groups = api.operation_groups();
+            for group in &groups.groups {
+                println!("{}", group.name.unwrap_or("(root)"));
+                for operation in &group.operations {
+                    println!("  {} {} {}", operation.name, operation.operation.method, operation.operation.path);
+                }
+            }
+            for err in &groups.errors {
+                eprintln!("{}", err);
+            }
+        }
+    }
+    Ok(())
+}
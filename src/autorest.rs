@@ -51,7 +51,7 @@ pub struct MsPageable {
 
 /// describes the format for specifying examples for request and response of an operation
 /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-examples
-pub type MsExamples = IndexMap<String, ReferenceOr<Operation>>;
+pub type MsExamples = IndexMap<String, ReferenceOr<example::Example>>;
 
 /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-long-running-operation-options
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
@@ -61,20 +61,15 @@ pub struct MsLongRunningOperationOptions {
     pub final_state_via: MsLongRunningOperationOptionsFinalStateVia,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum MsLongRunningOperationOptionsFinalStateVia {
+    #[default]
     AzureAsyncOperation,
     Location,
     OriginalUri,
 }
 
-impl Default for MsLongRunningOperationOptionsFinalStateVia {
-    fn default() -> Self {
-        MsLongRunningOperationOptionsFinalStateVia::AzureAsyncOperation
-    }
-}
-
 /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-parameter-location
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -117,7 +112,6 @@ pub struct MsParameterGrouping {
 #[cfg(test)]
 mod tests {
     use crate::MsPageable;
-    use serde_json;
 
     #[test]
     fn pageable_nextlinkname_may_be_null() {
@@ -0,0 +1,426 @@
+// Inspects and processes OpenAPI 2.0 documents.
+// cargo run --features cli -- stats ../azure-rest-api-specs/specification/vmware/resource-manager/Microsoft.AVS/stable/2020-03-20/vmware.json
+// Exits with 0 on success, 1 when a check finds problems or differences, and 2 on errors.
+
+use autorust_openapi::*;
+use clap::{ArgEnum, Parser, Subcommand};
+use serde_json::{json, Value};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+type Error = Box<dyn std::error::Error>;
+type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Parser)]
+#[clap(version, about = "Inspect and process OpenAPI 2.0 documents")]
+struct Args {
+    /// How to print the results
+    #[clap(long, arg_enum, global = true, default_value = "human")]
+    output: Output,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, ArgEnum)]
+enum Output {
+    Human,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, ArgEnum)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Count the paths, operations, definitions and other parts of a document
+    Stats { file: PathBuf },
+    /// List the operations of a document
+    Operations { file: PathBuf },
+    /// List the definitions of a document
+    Definitions { file: PathBuf },
+    /// Check documents for problems
+    Validate {
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Copy the definitions and parameters referenced in other files into a document
+    Bundle {
+        file: PathBuf,
+        /// Write to this file instead of stdout
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// Merge documents into one, such as the input files of an AutoRest tag
+    Merge {
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+        /// Write to this file instead of stdout
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// Convert a document between JSON and YAML
+    Convert {
+        file: PathBuf,
+        /// Write to this file instead of stdout
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+        /// The format to write, by default the one of the output file or the opposite of the input
+        #[clap(long, arg_enum)]
+        to: Option<Format>,
+    },
+    /// Extract the selected operations and what they use into a smaller document
+    Subset {
+        file: PathBuf,
+        /// Select operations with this tag
+        #[clap(long)]
+        tag: Vec<String>,
+        /// Select operations with an operationId matching this pattern, such as `Pool_*`
+        #[clap(long)]
+        operation_id: Vec<String>,
+        /// Select operations at paths starting with this prefix
+        #[clap(long)]
+        path_prefix: Vec<String>,
+        /// Write to this file instead of stdout
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// List the definitions, parameters and responses that are never referenced
+    Unused {
+        file: PathBuf,
+        /// Remove them from the file
+        #[clap(long)]
+        fix: bool,
+    },
+    /// List the differences between two documents
+    Diff { old: PathBuf, new: PathBuf },
+    /// Format documents in place
+    Fmt {
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+        /// Only report the files that are not formatted
+        #[clap(long)]
+        check: bool,
+        /// The number of spaces to indent JSON with
+        #[clap(long, default_value = "2")]
+        indent: usize,
+        /// Sort definitions by name
+        #[clap(long)]
+        sort_definitions: bool,
+        /// Sort paths
+        #[clap(long)]
+        sort_paths: bool,
+    },
+    /// List the documents of an azure-rest-api-specs checkout with their operation counts
+    Catalog {
+        /// The checkout or its specification directory
+        root: PathBuf,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+    match run(args) {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            exit(2);
+        }
+    }
+}
+
+/// Returns false if a check found problems or differences.
+fn run(args: Args) -> Result<bool> {
+    let output = args.output;
+    match args.command {
+        Command::Stats { file } => {
+            let spec = read_spec(&file)?;
+            let stats = json!({
+                "paths": spec.paths.len(),
+                "x-ms-paths": spec.x_ms_paths.len(),
+                "operations": spec.operations().count(),
+                "definitions": spec.definitions.len(),
+                "parameters": spec.parameters.len(),
+                "responses": spec.responses.len(),
+                "securityDefinitions": spec.security_definitions.len(),
+                "tags": spec.tags.len(),
+            });
+            match output {
+                Output::Json => print_json(&stats)?,
+                Output::Human => {
+                    for (name, count) in stats.as_object().into_iter().flatten() {
+                        println!("{}: {}", name, count);
+                    }
+                }
+            }
+        }
+        Command::Operations { file } => {
+            let spec = read_spec(&file)?;
+            let operations: Vec<_> = spec.operations().collect();
+            match output {
+                Output::Json => print_json(&Value::Array(
+                    operations
+                        .iter()
+                        .map(|operation| {
+                            json!({
+                                "method": operation.method,
+                                "path": operation.path,
+                                "operationId": operation.operation.operation_id,
+                            })
+                        })
+                        .collect(),
+                ))?,
+                Output::Human => {
+                    for operation in operations {
+                        let operation_id = operation.operation.operation_id.as_deref().unwrap_or_default();
+                        println!(
+                            "{:7} {} {}",
+                            operation.method.to_string().to_uppercase(),
+                            operation.path,
+                            operation_id
+                        );
+                    }
+                }
+            }
+        }
+        Command::Definitions { file } => {
+            let spec = read_spec(&file)?;
+            match output {
+                Output::Json => print_json(&json!(spec.definitions.keys().collect::<Vec<_>>()))?,
+                Output::Human => {
+                    for name in spec.definitions.keys() {
+                        println!("{}", name);
+                    }
+                }
+            }
+        }
+        Command::Validate { files } => {
+            let mut valid = true;
+            let mut results = Vec::new();
+            for file in files {
+                let errors: Vec<String> = match read_spec(&file) {
+                    Ok(spec) => spec.validate().iter().map(ToString::to_string).collect(),
+                    Err(err) => vec![err.to_string()],
+                };
+                valid &= errors.is_empty();
+                match output {
+                    Output::Json => results.push(json!({ "file": file, "errors": errors })),
+                    Output::Human => {
+                        for err in errors {
+                            println!("{}: {}", file.display(), err);
+                        }
+                    }
+                }
+            }
+            if output == Output::Json {
+                print_json(&Value::Array(results))?;
+            }
+            return Ok(valid);
+        }
+        Command::Bundle { file, out } => {
+            let spec = bundle(&file)?;
+            let format = out.as_deref().map(format_of).unwrap_or_else(|| format_of(&file));
+            write_spec(&spec, format, out.as_deref())?;
+        }
+        Command::Merge { files, out } => {
+            let merged = merge_files(&files)?;
+            for conflict in &merged.conflicts {
+                eprintln!("conflict: {}", conflict);
+            }
+            let format = out.as_deref().map(format_of).unwrap_or_else(|| format_of(&files[0]));
+            write_spec(&merged.spec, format, out.as_deref())?;
+            return Ok(merged.conflicts.is_empty());
+        }
+        Command::Convert { file, out, to } => {
+            let spec = read_spec(&file)?;
+            let format = match (to, out.as_deref()) {
+                (Some(format), _) => format,
+                (None, Some(out)) => format_of(out),
+                (None, None) if format_of(&file) == Format::Json => Format::Yaml,
+                (None, None) => Format::Json,
+            };
+            write_spec(&spec, format, out.as_deref())?;
+        }
+        Command::Subset {
+            file,
+            tag,
+            operation_id,
+            path_prefix,
+            out,
+        } => {
+            let selection = Selection {
+                tags: tag,
+                operation_ids: operation_id,
+                path_prefixes: path_prefix,
+            };
+            let spec = read_spec(&file)?.subset(&selection);
+            let format = out.as_deref().map(format_of).unwrap_or_else(|| format_of(&file));
+            write_spec(&spec, format, out.as_deref())?;
+        }
+        Command::Unused { file, fix } => {
+            let bytes = fs::read(&file).map_err(|err| format!("{}: {}", file.display(), err))?;
+            let unused = parse_spec(&file, &bytes)?.unused();
+            let components: Vec<String> = unused
+                .definitions
+                .iter()
+                .map(|name| Component::Definition(name.clone()))
+                .chain(unused.parameters.iter().map(|name| Component::Parameter(name.clone())))
+                .chain(unused.responses.iter().map(|name| Component::Response(name.clone())))
+                .map(|component| component.to_string())
+                .collect();
+            match output {
+                Output::Json => print_json(&json!(components))?,
+                Output::Human => {
+                    for component in &components {
+                        println!("{}", component);
+                    }
+                }
+            }
+            if fix {
+                // only the unused components are removed, so that the rest of the file is left as it was written
+                let text = match format_of(&file) {
+                    Format::Json => {
+                        let names: HashSet<(&str, &str)> = unused_sections(&unused)
+                            .iter()
+                            .flat_map(|&(section, names)| names.iter().map(move |name| (section, name.as_str())))
+                            .collect();
+                        remove_json_members(std::str::from_utf8(&bytes)?, |section, name| names.contains(&(section, name)))?
+                    }
+                    // YAML is written again from the parsed value, which keeps the order of keys and any content the `OpenAPI` type does not model
+                    Format::Yaml => {
+                        let mut value: OrderedValue = serde_yaml::from_slice(&bytes)?;
+                        remove_unused(&mut value, &unused);
+                        serde_yaml::to_string(&value)?
+                    }
+                };
+                fs::write(&file, text).map_err(|err| format!("{}: {}", file.display(), err))?;
+                return Ok(true);
+            }
+            return Ok(unused.is_empty());
+        }
+        Command::Diff { old, new } => {
+            let changes = diff(&read_spec(&old)?, &read_spec(&new)?);
+            match output {
+                Output::Json => print_json(&serde_json::to_value(&changes)?)?,
+                Output::Human => {
+                    for change in &changes {
+                        println!("{}", change);
+                    }
+                }
+            }
+            return Ok(changes.is_empty());
+        }
+        Command::Fmt {
+            files,
+            check,
+            indent,
+            sort_definitions,
+            sort_paths,
+        } => {
+            let options = FormatOptions {
+                indent,
+                sort_definitions,
+                sort_paths,
+            };
+            let mut formatted = true;
+            let mut unformatted = Vec::new();
+            for file in files {
+                let before = fs::read(&file).map_err(|err| format!("{}: {}", file.display(), err))?;
+                let after = format_spec(&file, &before, &options)?;
+                if before == after.as_bytes() {
+                    continue;
+                }
+                if check {
+                    formatted = false;
+                } else {
+                    fs::write(&file, after).map_err(|err| format!("{}: {}", file.display(), err))?;
+                }
+                unformatted.push(file);
+            }
+            match output {
+                Output::Json => print_json(&json!(unformatted))?,
+                Output::Human => {
+                    for file in unformatted {
+                        println!("{}", file.display());
+                    }
+                }
+            }
+            return Ok(formatted);
+        }
+        Command::Catalog { root } => {
+            let catalog = catalog(&root)?;
+            let failures: Vec<String> = catalog.failures.iter().map(ToString::to_string).collect();
+            match output {
+                Output::Json => print_json(&json!({ "entries": catalog.entries, "failures": failures }))?,
+                Output::Human => {
+                    for entry in &catalog.entries {
+                        println!("{} {}", entry.path.display(), entry.operations);
+                    }
+                    for failure in &failures {
+                        eprintln!("error: {}", failure);
+                    }
+                }
+            }
+            return Ok(failures.is_empty());
+        }
+    }
+    Ok(true)
+}
+
+fn format_of(path: &Path) -> Format {
+    if is_yaml(path) {
+        Format::Yaml
+    } else {
+        Format::Json
+    }
+}
+
+fn to_string(spec: &OpenAPI, format: Format) -> Result<String> {
+    Ok(match format {
+        Format::Json => serde_json::to_string_pretty(spec)? + "\n",
+        Format::Yaml => serde_yaml::to_string(spec)?,
+    })
+}
+
+fn write_spec(spec: &OpenAPI, format: Format, out: Option<&Path>) -> Result<()> {
+    let text = to_string(spec, format)?;
+    match out {
+        Some(out) => fs::write(out, text).map_err(|err| format!("{}: {}", out.display(), err))?,
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Removes the unused definitions, parameters and responses from a document, keeping the order of the rest.
+fn remove_unused(spec: &mut OrderedValue, unused: &Unused) {
+    let spec = match spec {
+        OrderedValue::Object(spec) => spec,
+        _ => return,
+    };
+    for &(section, names) in unused_sections(unused).iter() {
+        if let Some(OrderedValue::Object(components)) = spec.get_mut(section) {
+            let names: HashSet<&str> = names.iter().map(String::as_str).collect();
+            components.retain(|name, _| !names.contains(name.as_str()));
+        }
+    }
+}
+
+fn unused_sections(unused: &Unused) -> [(&'static str, &Vec<String>); 3] {
+    [
+        ("definitions", &unused.definitions),
+        ("parameters", &unused.parameters),
+        ("responses", &unused.responses),
+    ]
+}
+
+fn print_json(value: &Value) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
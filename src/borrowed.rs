@@ -0,0 +1,620 @@
+//! A document whose sections are parsed on demand
+//! Only the names of paths, definitions, parameters, responses and security definitions are read upfront.
+//! Their values are kept as raw JSON slices of the input and only deserialized into the owned types when asked for,
+//! so reading a large document to look at a few definitions does not allocate for every string in it.
+//! A section is parsed either into the owned types or into the borrowed `BorrowedPathItem`, `BorrowedOperation`,
+//! `BorrowedParameter`, `BorrowedResponse` and `BorrowedSchema`. These borrow their names, descriptions and other strings
+//! from the input unless they contain escapes, and keep the fields that are not strings as raw slices in `rest`.
+
+use crate::*;
+use indexmap::IndexMap;
+use serde::{
+    de::{DeserializeOwned, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::value::RawValue;
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#swagger-object
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BorrowedOpenAPI<'a> {
+    #[serde(borrow)]
+    pub swagger: Cow<'a, str>,
+    #[serde(borrow)]
+    pub info: &'a RawValue,
+    #[serde(borrow, default)]
+    pub host: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    pub base_path: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    pub schemes: Option<&'a RawValue>,
+    #[serde(borrow, default)]
+    pub consumes: Option<&'a RawValue>,
+    #[serde(borrow, default)]
+    pub produces: Option<&'a RawValue>,
+    #[serde(borrow, deserialize_with = "borrowed_map")]
+    pub paths: IndexMap<Cow<'a, str>, &'a RawValue>,
+    #[serde(borrow, default, rename = "x-ms-paths", deserialize_with = "borrowed_map")]
+    pub x_ms_paths: IndexMap<Cow<'a, str>, &'a RawValue>,
+    #[serde(borrow, default, deserialize_with = "borrowed_map")]
+    pub definitions: IndexMap<Cow<'a, str>, &'a RawValue>,
+    #[serde(borrow, default, deserialize_with = "borrowed_map")]
+    pub parameters: IndexMap<Cow<'a, str>, &'a RawValue>,
+    #[serde(borrow, default, deserialize_with = "borrowed_map")]
+    pub responses: IndexMap<Cow<'a, str>, &'a RawValue>,
+    #[serde(borrow, default, deserialize_with = "borrowed_map")]
+    pub security_definitions: IndexMap<Cow<'a, str>, &'a RawValue>,
+    #[serde(borrow, default)]
+    pub security: Option<&'a RawValue>,
+    #[serde(borrow, default)]
+    pub tags: Option<&'a RawValue>,
+    #[serde(borrow, default)]
+    pub external_docs: Option<&'a RawValue>,
+    #[serde(borrow, default, rename = "x-ms-parameterized-host")]
+    pub x_ms_parameterized_host: Option<&'a RawValue>,
+}
+
+impl<'a> BorrowedOpenAPI<'a> {
+    /// Deserializes a JSON document, borrowing from the bytes.
+    pub fn from_slice(bytes: &'a [u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(bytes)
+    }
+
+    pub fn path_item(&self, path: &str) -> Option<Result<ReferenceOr<PathItem>, serde_json::Error>> {
+        self.paths.get(path).map(|raw| parse(raw))
+    }
+
+    pub fn definition(&self, name: &str) -> Option<Result<ReferenceOr<Schema>, serde_json::Error>> {
+        self.definitions.get(name).map(|raw| parse(raw))
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<Result<Parameter, serde_json::Error>> {
+        self.parameters.get(name).map(|raw| parse(raw))
+    }
+
+    pub fn x_ms_path_item(&self, path: &str) -> Option<Result<ReferenceOr<PathItem>, serde_json::Error>> {
+        self.x_ms_paths.get(path).map(|raw| parse(raw))
+    }
+
+    pub fn response(&self, name: &str) -> Option<Result<ReferenceOr<Response>, serde_json::Error>> {
+        self.responses.get(name).map(|raw| parse(raw))
+    }
+
+    pub fn security_definition(&self, name: &str) -> Option<Result<Security, serde_json::Error>> {
+        self.security_definitions.get(name).map(|raw| parse(raw))
+    }
+
+    pub fn borrowed_path_item(&self, path: &str) -> Option<Result<BorrowedPathItem<'a>, serde_json::Error>> {
+        self.paths.get(path).map(|raw| parse(raw))
+    }
+
+    pub fn borrowed_definition(&self, name: &str) -> Option<Result<BorrowedSchema<'a>, serde_json::Error>> {
+        self.definitions.get(name).map(|raw| parse(raw))
+    }
+
+    pub fn borrowed_parameter(&self, name: &str) -> Option<Result<BorrowedParameter<'a>, serde_json::Error>> {
+        self.parameters.get(name).map(|raw| parse(raw))
+    }
+
+    /// Deserializes everything into the owned types.
+    pub fn to_openapi(&self) -> Result<OpenAPI, serde_json::Error> {
+        Ok(OpenAPI {
+            swagger: self.swagger.clone().into_owned(),
+            info: parse(self.info)?,
+            host: self.host.clone().map(Cow::into_owned),
+            base_path: self.base_path.clone().map(Cow::into_owned),
+            schemes: parse_or_default(self.schemes)?,
+            consumes: parse_or_default(self.consumes)?,
+            produces: parse_or_default(self.produces)?,
+            paths: parse_map(&self.paths)?,
+            x_ms_paths: parse_map(&self.x_ms_paths)?,
+            definitions: parse_map(&self.definitions)?,
+            parameters: parse_map(&self.parameters)?,
+            responses: self
+                .responses
+                .iter()
+                .map(|(status_code, raw)| Ok((serde_json::from_value(status_code.as_ref().into())?, parse(raw)?)))
+                .collect::<Result<_, serde_json::Error>>()?,
+            security_definitions: parse_map(&self.security_definitions)?,
+            security: parse_or_default(self.security)?,
+            tags: parse_or_default(self.tags)?,
+            external_docs: self.external_docs.map(parse).transpose()?,
+            x_ms_parameterized_host: self.x_ms_parameterized_host.map(parse).transpose()?,
+        })
+    }
+}
+
+/// Deserializes a map, borrowing the keys unless they contain escapes.
+/// The `Deserialize` implementation of `Cow` always allocates, even with `#[serde(borrow)]`, once it is inside of a map.
+fn borrowed_map<'de, D, V>(deserializer: D) -> Result<IndexMap<Cow<'de, str>, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    BorrowedMap::deserialize(deserializer).map(|map| map.0)
+}
+
+/// A map with borrowed keys whose entries are named in errors.
+struct BorrowedMap<'a, V>(IndexMap<Cow<'a, str>, V>);
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for BorrowedMap<'de, V> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(BorrowedMapVisitor(PhantomData))
+    }
+}
+
+struct BorrowedMapVisitor<V>(PhantomData<V>);
+
+impl<'de, V: Deserialize<'de>> Visitor<'de> for BorrowedMapVisitor<V> {
+    type Value = BorrowedMap<'de, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map = IndexMap::with_capacity(access.size_hint().unwrap_or_default());
+        while let Some(key) = access.next_key::<BorrowedKey>()? {
+            let value = next_value(&mut access, &key.0)?;
+            map.insert(key.0, value);
+        }
+        Ok(BorrowedMap(map))
+    }
+}
+
+/// A string that is borrowed from the input unless it contains escapes.
+pub(crate) struct BorrowedKey<'a>(pub(crate) Cow<'a, str>);
+
+impl<'de> Deserialize<'de> for BorrowedKey<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(BorrowedKeyVisitor)
+    }
+}
+
+struct BorrowedKeyVisitor;
+
+impl<'de> Visitor<'de> for BorrowedKeyVisitor {
+    type Value = BorrowedKey<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(BorrowedKey(Cow::Borrowed(value)))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(BorrowedKey(Cow::Owned(value.to_owned())))
+    }
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#path-item-object
+/// A path item or a reference to one, borrowing its strings from the input.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BorrowedPathItem<'a> {
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get: Option<BorrowedOperation<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post: Option<BorrowedOperation<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub put: Option<BorrowedOperation<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patch: Option<BorrowedOperation<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<BorrowedOperation<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<BorrowedOperation<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<BorrowedOperation<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<BorrowedParameter<'a>>,
+    /// The other fields, as raw JSON
+    #[serde(flatten)]
+    pub rest: IndexMap<Cow<'a, str>, &'a RawValue>,
+}
+
+impl<'a> BorrowedPathItem<'a> {
+    pub fn to_path_item(&self) -> Result<ReferenceOr<PathItem>, serde_json::Error> {
+        to_owned_type(self)
+    }
+}
+
+impl<'de> BorrowFields<'de> for BorrowedPathItem<'de> {
+    fn borrow_field<A: MapAccess<'de>>(&mut self, key: Cow<'de, str>, map: &mut A) -> Result<(), A::Error> {
+        match key.as_ref() {
+            "$ref" => self.reference = Some(borrowed_str(map, &key)?),
+            "get" => self.get = Some(next_value(map, &key)?),
+            "post" => self.post = Some(next_value(map, &key)?),
+            "put" => self.put = Some(next_value(map, &key)?),
+            "patch" => self.patch = Some(next_value(map, &key)?),
+            "delete" => self.delete = Some(next_value(map, &key)?),
+            "options" => self.options = Some(next_value(map, &key)?),
+            "head" => self.head = Some(next_value(map, &key)?),
+            "parameters" => self.parameters = next_value(map, &key)?,
+            _ => self.borrow_rest(key, map)?,
+        }
+        Ok(())
+    }
+
+    fn rest(&mut self) -> &mut IndexMap<Cow<'de, str>, &'de RawValue> {
+        &mut self.rest
+    }
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#operation-object
+/// An operation, borrowing its strings from the input.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BorrowedOperation<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<Cow<'a, str>>,
+    #[serde(rename = "operationId", skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<Cow<'a, str>>,
+    pub responses: IndexMap<Cow<'a, str>, BorrowedResponse<'a>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<BorrowedParameter<'a>>,
+    /// The other fields, as raw JSON
+    #[serde(flatten)]
+    pub rest: IndexMap<Cow<'a, str>, &'a RawValue>,
+}
+
+impl<'a> BorrowedOperation<'a> {
+    pub fn to_operation(&self) -> Result<Operation, serde_json::Error> {
+        to_owned_type(self)
+    }
+}
+
+impl<'de> BorrowFields<'de> for BorrowedOperation<'de> {
+    fn borrow_field<A: MapAccess<'de>>(&mut self, key: Cow<'de, str>, map: &mut A) -> Result<(), A::Error> {
+        match key.as_ref() {
+            "summary" => self.summary = Some(borrowed_str(map, &key)?),
+            "description" => self.description = Some(borrowed_str(map, &key)?),
+            "tags" => self.tags = borrowed_strs(map, &key)?,
+            "operationId" => self.operation_id = Some(borrowed_str(map, &key)?),
+            "responses" => self.responses = next_value::<BorrowedMap<_>, _>(map, &key)?.0,
+            "parameters" => self.parameters = next_value(map, &key)?,
+            _ => self.borrow_rest(key, map)?,
+        }
+        Ok(())
+    }
+
+    fn rest(&mut self) -> &mut IndexMap<Cow<'de, str>, &'de RawValue> {
+        &mut self.rest
+    }
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#parameter-object
+/// A parameter or a reference to one, borrowing its strings from the input.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BorrowedParameter<'a> {
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<BorrowedSchema<'a>>,
+    #[serde(rename = "x-ms-client-name", skip_serializing_if = "Option::is_none")]
+    pub x_ms_client_name: Option<Cow<'a, str>>,
+    /// The other fields, as raw JSON
+    #[serde(flatten)]
+    pub rest: IndexMap<Cow<'a, str>, &'a RawValue>,
+}
+
+impl<'a> BorrowedParameter<'a> {
+    pub fn to_parameter(&self) -> Result<ReferenceOr<Parameter>, serde_json::Error> {
+        to_owned_type(self)
+    }
+}
+
+impl<'de> BorrowFields<'de> for BorrowedParameter<'de> {
+    fn borrow_field<A: MapAccess<'de>>(&mut self, key: Cow<'de, str>, map: &mut A) -> Result<(), A::Error> {
+        match key.as_ref() {
+            "$ref" => self.reference = Some(borrowed_str(map, &key)?),
+            "name" => self.name = Some(borrowed_str(map, &key)?),
+            "description" => self.description = Some(borrowed_str(map, &key)?),
+            "schema" => self.schema = Some(next_value(map, &key)?),
+            "x-ms-client-name" => self.x_ms_client_name = Some(borrowed_str(map, &key)?),
+            _ => self.borrow_rest(key, map)?,
+        }
+        Ok(())
+    }
+
+    fn rest(&mut self) -> &mut IndexMap<Cow<'de, str>, &'de RawValue> {
+        &mut self.rest
+    }
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#responseObject
+/// A response, borrowing its strings from the input.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BorrowedResponse<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<BorrowedSchema<'a>>,
+    /// The other fields, as raw JSON
+    #[serde(flatten)]
+    pub rest: IndexMap<Cow<'a, str>, &'a RawValue>,
+}
+
+impl<'a> BorrowedResponse<'a> {
+    pub fn to_response(&self) -> Result<Response, serde_json::Error> {
+        to_owned_type(self)
+    }
+}
+
+impl<'de> BorrowFields<'de> for BorrowedResponse<'de> {
+    fn borrow_field<A: MapAccess<'de>>(&mut self, key: Cow<'de, str>, map: &mut A) -> Result<(), A::Error> {
+        match key.as_ref() {
+            "description" => self.description = Some(borrowed_str(map, &key)?),
+            "schema" => self.schema = Some(next_value(map, &key)?),
+            _ => self.borrow_rest(key, map)?,
+        }
+        Ok(())
+    }
+
+    fn rest(&mut self) -> &mut IndexMap<Cow<'de, str>, &'de RawValue> {
+        &mut self.rest
+    }
+}
+
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#schemaObject
+/// A schema or a reference to one, borrowing its strings from the input.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BorrowedSchema<'a> {
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<Cow<'a, str>>,
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub properties: IndexMap<Cow<'a, str>, BorrowedSchema<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<BorrowedSchema<'a>>>,
+    #[serde(rename = "allOf", skip_serializing_if = "Vec::is_empty")]
+    pub all_of: Vec<BorrowedSchema<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Cow<'a, str>>,
+    #[serde(rename = "x-ms-client-name", skip_serializing_if = "Option::is_none")]
+    pub x_ms_client_name: Option<Cow<'a, str>>,
+    /// The other fields, as raw JSON
+    #[serde(flatten)]
+    pub rest: IndexMap<Cow<'a, str>, &'a RawValue>,
+}
+
+impl<'a> BorrowedSchema<'a> {
+    pub fn to_schema(&self) -> Result<ReferenceOr<Schema>, serde_json::Error> {
+        to_owned_type(self)
+    }
+}
+
+impl<'de> BorrowFields<'de> for BorrowedSchema<'de> {
+    fn borrow_field<A: MapAccess<'de>>(&mut self, key: Cow<'de, str>, map: &mut A) -> Result<(), A::Error> {
+        match key.as_ref() {
+            "$ref" => self.reference = Some(borrowed_str(map, &key)?),
+            "description" => self.description = Some(borrowed_str(map, &key)?),
+            "title" => self.title = Some(borrowed_str(map, &key)?),
+            "format" => self.format = Some(borrowed_str(map, &key)?),
+            "required" => self.required = borrowed_strs(map, &key)?,
+            "properties" => self.properties = next_value::<BorrowedMap<_>, _>(map, &key)?.0,
+            "items" => self.items = Some(Box::new(next_value(map, &key)?)),
+            "allOf" => self.all_of = next_value(map, &key)?,
+            "discriminator" => self.discriminator = Some(borrowed_str(map, &key)?),
+            "x-ms-client-name" => self.x_ms_client_name = Some(borrowed_str(map, &key)?),
+            _ => self.borrow_rest(key, map)?,
+        }
+        Ok(())
+    }
+
+    fn rest(&mut self) -> &mut IndexMap<Cow<'de, str>, &'de RawValue> {
+        &mut self.rest
+    }
+}
+
+macro_rules! deserialize_borrowed {
+    ($($type:ident: $expecting:literal),*) => {
+        $(
+            impl<'de> Deserialize<'de> for $type<'de> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    deserializer.deserialize_map(BorrowFieldsVisitor(PhantomData, $expecting))
+                }
+            }
+        )*
+    };
+}
+
+deserialize_borrowed!(
+    BorrowedPathItem: "a path item",
+    BorrowedOperation: "an operation",
+    BorrowedParameter: "a parameter",
+    BorrowedResponse: "a response",
+    BorrowedSchema: "a schema"
+);
+
+/// A borrowed type that reads its string fields as borrowed strings and keeps the others as raw JSON.
+trait BorrowFields<'de>: Default {
+    fn borrow_field<A: MapAccess<'de>>(&mut self, key: Cow<'de, str>, map: &mut A) -> Result<(), A::Error>;
+
+    fn rest(&mut self) -> &mut IndexMap<Cow<'de, str>, &'de RawValue>;
+
+    fn borrow_rest<A: MapAccess<'de>>(&mut self, key: Cow<'de, str>, map: &mut A) -> Result<(), A::Error> {
+        let value = next_value(map, &key)?;
+        self.rest().insert(key, value);
+        Ok(())
+    }
+}
+
+struct BorrowFieldsVisitor<T>(PhantomData<T>, &'static str);
+
+impl<'de, T: BorrowFields<'de>> Visitor<'de> for BorrowFieldsVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.1)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+        let mut value = T::default();
+        while let Some(key) = map.next_key::<BorrowedKey>()? {
+            value.borrow_field(key.0, &mut map)?;
+        }
+        Ok(value)
+    }
+}
+
+fn borrowed_str<'de, A: MapAccess<'de>>(map: &mut A, key: &str) -> Result<Cow<'de, str>, A::Error> {
+    next_value::<BorrowedKey, _>(map, key).map(|value| value.0)
+}
+
+fn borrowed_strs<'de, A: MapAccess<'de>>(map: &mut A, key: &str) -> Result<Vec<Cow<'de, str>>, A::Error> {
+    next_value::<Vec<BorrowedKey>, _>(map, key).map(|values| values.into_iter().map(|value| value.0).collect())
+}
+
+/// Converts a borrowed type to its owned type by writing it back to JSON.
+fn to_owned_type<T: DeserializeOwned>(borrowed: &impl Serialize) -> Result<T, serde_json::Error> {
+    serde_json::from_slice(&serde_json::to_vec(borrowed)?)
+}
+
+fn parse<'de, T: Deserialize<'de>>(raw: &'de RawValue) -> Result<T, serde_json::Error> {
+    serde_json::from_str(raw.get())
+}
+
+fn parse_or_default<'de, T: Deserialize<'de> + Default>(raw: Option<&'de RawValue>) -> Result<T, serde_json::Error> {
+    raw.map(parse).transpose().map(Option::unwrap_or_default)
+}
+
+fn parse_map<'de, T: Deserialize<'de>>(map: &IndexMap<Cow<str>, &'de RawValue>) -> Result<IndexMap<String, T>, serde_json::Error> {
+    map.iter().map(|(name, raw)| Ok((name.clone().into_owned(), parse(raw)?))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn borrows_and_converts_to_owned() {
+        let bytes = serde_json::to_vec(&json!({
+            "swagger": "2.0",
+            "info": { "title": "Pets", "version": "1" },
+            "host": "pets.example.com",
+            "paths": {
+                "/pets": { "get": { "operationId": "Pets_List", "responses": { "200": { "description": "ok" } } } }
+            },
+            "definitions": {
+                "Pet": { "type": "object", "properties": { "name": { "type": "string" } } },
+                "Pet\"s": { "type": "string" }
+            },
+            "responses": { "default": { "description": "error" } },
+            "tags": [{ "name": "Pets" }]
+        }))
+        .unwrap();
+        let borrowed = BorrowedOpenAPI::from_slice(&bytes).unwrap();
+        assert!(matches!(borrowed.definitions.get_index(0), Some((Cow::Borrowed("Pet"), _))));
+        assert!(matches!(borrowed.definitions.get_index(1), Some((Cow::Owned(_), _))));
+        match borrowed.definition("Pet").unwrap().unwrap() {
+            ReferenceOr::Item(schema) => assert_eq!(schema.properties.len(), 1),
+            ReferenceOr::Reference { .. } => panic!("expected a schema"),
+        }
+        assert!(borrowed.definition("Cat").is_none());
+
+        let owned: OpenAPI = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(borrowed.to_openapi().unwrap(), owned);
+    }
+
+    #[test]
+    fn borrows_the_strings_of_sections() {
+        let bytes = br##"{
+            "swagger": "2.0",
+            "info": { "title": "Pets", "version": "1" },
+            "paths": {
+                "/pets/{name}": {
+                    "parameters": [{ "$ref": "#/parameters/Name" }],
+                    "get": {
+                        "operationId": "Pets_Get",
+                        "tags": ["Pets"],
+                        "parameters": [{ "name": "api-version", "in": "query", "type": "string", "required": true }],
+                        "responses": { "200": { "description": "the pet", "schema": { "$ref": "#/definitions/Pet" } } },
+                        "x-ms-examples": { "Get": { "$ref": "./examples/Get.json" } }
+                    }
+                }
+            },
+            "x-ms-paths": { "/pets?op=list": { "get": { "responses": { "200": { "description": "ok" } } } } },
+            "definitions": {
+                "Pet": {
+                    "description": "A \"pet\"",
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string", "readOnly": true },
+                        "tags": { "type": "array", "items": { "type": "string", "enum": ["a", "b"] } },
+                        "owner": { "$ref": "#/definitions/Owner", "description": "the owner" }
+                    },
+                    "additionalProperties": false
+                }
+            },
+            "parameters": { "Name": { "name": "name", "in": "path", "required": true, "type": "string" } },
+            "responses": { "default": { "description": "error" } },
+            "securityDefinitions": { "key": { "type": "apiKey", "name": "key", "in": "header" } }
+        }"##;
+        let borrowed = BorrowedOpenAPI::from_slice(bytes).unwrap();
+        let owned: OpenAPI = serde_json::from_slice(bytes).unwrap();
+
+        let pet = borrowed.borrowed_definition("Pet").unwrap().unwrap();
+        assert!(matches!(pet.description, Some(Cow::Owned(_))));
+        assert!(matches!(pet.required.as_slice(), [Cow::Borrowed("name")]));
+        assert!(matches!(pet.properties.get_index(1), Some((Cow::Borrowed("tags"), _))));
+        assert_eq!(pet.properties["tags"].items.as_ref().unwrap().rest["enum"].get(), r#"["a", "b"]"#);
+        assert_eq!(pet.properties["owner"].reference.as_deref(), Some("#/definitions/Owner"));
+        assert_eq!(pet.rest["additionalProperties"].get(), "false");
+        assert_eq!(pet.to_schema().unwrap(), owned.definitions["Pet"]);
+
+        let item = borrowed.borrowed_path_item("/pets/{name}").unwrap().unwrap();
+        let get = item.get.as_ref().unwrap();
+        assert!(matches!(get.operation_id, Some(Cow::Borrowed("Pets_Get"))));
+        assert!(matches!(get.parameters[0].name, Some(Cow::Borrowed("api-version"))));
+        assert!(matches!(get.responses["200"].description, Some(Cow::Borrowed("the pet"))));
+        assert_eq!(item.to_path_item().unwrap(), owned.paths["/pets/{name}"]);
+        match &owned.paths["/pets/{name}"] {
+            ReferenceOr::Item(path_item) => assert_eq!(Some(get.to_operation().unwrap()), path_item.get),
+            ReferenceOr::Reference { .. } => panic!("expected a path item"),
+        }
+
+        let name = borrowed.borrowed_parameter("Name").unwrap().unwrap();
+        assert_eq!(ReferenceOr::Item(owned.parameters["Name"].clone()), name.to_parameter().unwrap());
+
+        assert_eq!(
+            borrowed.x_ms_path_item("/pets?op=list").unwrap().unwrap(),
+            owned.x_ms_paths["/pets?op=list"]
+        );
+        assert_eq!(borrowed.response("default").unwrap().unwrap(), owned.responses[0]);
+        assert_eq!(
+            borrowed.security_definition("key").unwrap().unwrap(),
+            owned.security_definitions["key"]
+        );
+        assert!(borrowed.borrowed_definition("Owner").is_none());
+    }
+}
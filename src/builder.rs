@@ -0,0 +1,512 @@
+//! Chainable constructors for building documents in code
+//! `Schema::object().property("id", Schema::string().read_only()).required("id")`
+
+use crate::*;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+impl<T> ReferenceOr<T> {
+    /// A reference to `#/{section}/{name}` without any sibling fields.
+    pub fn local(section: &str, name: &str) -> Self {
+        ReferenceOr::Reference {
+            reference: Reference::local(section, name),
+            title: None,
+            description: None,
+            type_: None,
+            read_only: None,
+            x_ms_client_flatten: None,
+        }
+    }
+}
+
+impl<T> From<T> for ReferenceOr<T> {
+    fn from(item: T) -> Self {
+        ReferenceOr::Item(item)
+    }
+}
+
+impl OpenAPI {
+    /// A Swagger 2.0 document with the given title and version.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        OpenAPI {
+            swagger: "2.0".to_owned(),
+            info: Info {
+                title: Some(title.into()),
+                version: Some(version.into()),
+                ..Info::default()
+            },
+            ..OpenAPI::default()
+        }
+    }
+
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = Some(base_path.into());
+        self
+    }
+
+    pub fn scheme(mut self, scheme: Scheme) -> Self {
+        self.schemes.push(scheme);
+        self
+    }
+
+    pub fn consumes(mut self, mime_type: impl Into<String>) -> Self {
+        self.consumes.push(mime_type.into());
+        self
+    }
+
+    pub fn produces(mut self, mime_type: impl Into<String>) -> Self {
+        self.produces.push(mime_type.into());
+        self
+    }
+
+    /// Adds a path, replacing any path item already at it.
+    pub fn path(mut self, path: impl Into<String>, item: impl Into<ReferenceOr<PathItem>>) -> Self {
+        self.paths.insert(path.into(), item.into());
+        self
+    }
+
+    /// Adds an operation to the path item at `path`, creating the path item if needed.
+    /// Does nothing to a path that is a reference.
+    pub fn operation(mut self, path: impl Into<String>, method: Method, operation: Operation) -> Self {
+        if let ReferenceOr::Item(item) = self.paths.entry(path.into()).or_insert_with(|| PathItem::default().into()) {
+            *item = std::mem::take(item).operation(method, operation);
+        }
+        self
+    }
+
+    pub fn definition(mut self, name: impl Into<String>, schema: impl Into<ReferenceOr<Schema>>) -> Self {
+        self.definitions.insert(name.into(), schema.into());
+        self
+    }
+
+    pub fn parameter(mut self, name: impl Into<String>, parameter: Parameter) -> Self {
+        self.parameters.insert(name.into(), parameter);
+        self
+    }
+
+    pub fn response(mut self, status_code: StatusCode, response: impl Into<ReferenceOr<Response>>) -> Self {
+        self.responses.insert(status_code, response.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+}
+
+impl PathItem {
+    /// Sets the operation for an HTTP method, replacing any already set.
+    pub fn operation(mut self, method: Method, operation: Operation) -> Self {
+        let slot = match method {
+            Method::Get => &mut self.get,
+            Method::Post => &mut self.post,
+            Method::Put => &mut self.put,
+            Method::Patch => &mut self.patch,
+            Method::Delete => &mut self.delete,
+            Method::Options => &mut self.options,
+            Method::Head => &mut self.head,
+        };
+        *slot = Some(operation);
+        self
+    }
+
+    /// Adds a parameter shared by all of the operations.
+    pub fn parameter(mut self, parameter: impl Into<ReferenceOr<Parameter>>) -> Self {
+        self.parameters.push(parameter.into());
+        self
+    }
+}
+
+impl Operation {
+    pub fn new(operation_id: impl Into<String>) -> Self {
+        Operation {
+            operation_id: Some(operation_id.into()),
+            ..Operation::default()
+        }
+    }
+
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn parameter(mut self, parameter: impl Into<ReferenceOr<Parameter>>) -> Self {
+        self.parameters.push(parameter.into());
+        self
+    }
+
+    pub fn response(mut self, status_code: StatusCode, response: Response) -> Self {
+        self.responses.insert(status_code, response);
+        self
+    }
+
+    pub fn long_running(mut self) -> Self {
+        self.x_ms_long_running_operation = Some(true);
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = Some(true);
+        self
+    }
+}
+
+impl Parameter {
+    pub fn new(name: impl Into<String>, kind: ParameterKind) -> Self {
+        Parameter {
+            name: name.into(),
+            kind,
+            required: None,
+            x_ms_parameter_location: None,
+            x_ms_skip_url_encoding: None,
+            x_ms_parameter_grouping: None,
+            x_ms_client_request_id: None,
+        }
+    }
+
+    /// A required string path parameter.
+    pub fn path(name: impl Into<String>) -> Self {
+        Parameter::new(name, ParameterKind::Path(NonBodyParameter::default()))
+            .data_type(DataType::String)
+            .required()
+    }
+
+    pub fn query(name: impl Into<String>, type_: DataType) -> Self {
+        Parameter::new(name, ParameterKind::Query(NonBodyParameter::default())).data_type(type_)
+    }
+
+    pub fn header(name: impl Into<String>, type_: DataType) -> Self {
+        Parameter::new(name, ParameterKind::Header(NonBodyParameter::default())).data_type(type_)
+    }
+
+    /// A form parameter, which may be a `DataType::File`.
+    pub fn form_data(name: impl Into<String>, type_: DataType) -> Self {
+        Parameter::new(name, ParameterKind::FormData(NonBodyParameter::default())).data_type(type_)
+    }
+
+    pub fn body(name: impl Into<String>, schema: impl Into<ReferenceOr<Schema>>) -> Self {
+        Parameter::new(
+            name,
+            ParameterKind::Body(BodyParameter {
+                description: None,
+                schema: schema.into(),
+                x_ms_client_name: None,
+                x_ms_client_flatten: None,
+                extra: IndexMap::new(),
+            }),
+        )
+    }
+
+    /// A reference to `#/parameters/{name}`.
+    pub fn reference(name: &str) -> ReferenceOr<Parameter> {
+        ReferenceOr::local("parameters", name)
+    }
+
+    /// Does nothing to a body parameter, as do the other methods setting `SchemaCommon` fields.
+    pub fn data_type(mut self, type_: DataType) -> Self {
+        if let Some(parameter) = self.non_body_mut() {
+            parameter.common.type_ = Some(type_);
+        }
+        self
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        if let Some(parameter) = self.non_body_mut() {
+            parameter.common.format = Some(format.into());
+        }
+        self
+    }
+
+    pub fn default_value(mut self, value: impl Into<Value>) -> Self {
+        if let Some(parameter) = self.non_body_mut() {
+            parameter.common.default = Some(value.into());
+        }
+        self
+    }
+
+    pub fn enum_value(mut self, value: impl Into<Value>) -> Self {
+        if let Some(parameter) = self.non_body_mut() {
+            parameter.common.enum_.push(value.into());
+        }
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        match &mut self.kind {
+            ParameterKind::Body(body) => body.description = Some(description.into()),
+            _ => {
+                if let Some(parameter) = self.non_body_mut() {
+                    parameter.common.description = Some(description.into());
+                }
+            }
+        }
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = Some(true);
+        self
+    }
+
+    /// Marks a global parameter as a parameter of the method instead of a client property.
+    pub fn method_location(mut self) -> Self {
+        self.x_ms_parameter_location = Some(MsParameterLocation::Method);
+        self
+    }
+}
+
+impl Response {
+    pub fn new(description: impl Into<String>) -> Self {
+        Response {
+            description: Some(description.into()),
+            ..Response::default()
+        }
+    }
+
+    pub fn schema(mut self, schema: impl Into<ReferenceOr<Schema>>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, header: impl Into<ReferenceOr<Header>>) -> Self {
+        self.headers.insert(name.into(), header.into());
+        self
+    }
+
+    pub fn error_response(mut self) -> Self {
+        self.x_ms_error_response = Some(true);
+        self
+    }
+}
+
+impl Schema {
+    pub fn new(type_: DataType) -> Self {
+        Schema {
+            common: SchemaCommon {
+                type_: Some(type_),
+                ..SchemaCommon::default()
+            },
+            ..Schema::default()
+        }
+    }
+
+    pub fn object() -> Self {
+        Schema::new(DataType::Object)
+    }
+
+    pub fn string() -> Self {
+        Schema::new(DataType::String)
+    }
+
+    pub fn integer() -> Self {
+        Schema::new(DataType::Integer)
+    }
+
+    pub fn number() -> Self {
+        Schema::new(DataType::Number)
+    }
+
+    pub fn boolean() -> Self {
+        Schema::new(DataType::Boolean)
+    }
+
+    pub fn array(items: impl Into<ReferenceOr<Schema>>) -> Self {
+        let mut schema = Schema::new(DataType::Array);
+        schema.common.items = Box::new(Some(items.into()));
+        schema
+    }
+
+    /// A map from strings to the given values.
+    pub fn map(values: impl Into<ReferenceOr<Schema>>) -> Self {
+        Schema::object().additional_properties(values)
+    }
+
+    /// A reference to `#/definitions/{name}`.
+    pub fn reference(name: &str) -> ReferenceOr<Schema> {
+        ReferenceOr::local("definitions", name)
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.common.description = Some(description.into());
+        self
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.common.format = Some(format.into());
+        self
+    }
+
+    pub fn default_value(mut self, value: impl Into<Value>) -> Self {
+        self.common.default = Some(value.into());
+        self
+    }
+
+    pub fn example(mut self, value: impl Into<Value>) -> Self {
+        self.example = Some(value.into());
+        self
+    }
+
+    pub fn enum_value(mut self, value: impl Into<Value>) -> Self {
+        self.common.enum_.push(value.into());
+        self
+    }
+
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.common.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Adds a property, replacing any property already with the name.
+    pub fn property(mut self, name: impl Into<String>, schema: impl Into<ReferenceOr<Schema>>) -> Self {
+        self.properties.insert(name.into(), schema.into());
+        self
+    }
+
+    /// Adds a name to the required properties.
+    pub fn required(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        if !self.required.contains(&name) {
+            self.required.push(name);
+        }
+        self
+    }
+
+    pub fn additional_properties(mut self, schema: impl Into<ReferenceOr<Schema>>) -> Self {
+        self.additional_properties = Box::new(Some(AdditionalProperties::Schema(schema.into())));
+        self
+    }
+
+    /// Adds a schema to `allOf`, such as the parent of a polymorphic type.
+    pub fn all_of(mut self, schema: impl Into<ReferenceOr<Schema>>) -> Self {
+        self.all_of.push(schema.into());
+        self
+    }
+
+    pub fn discriminator(mut self, property: impl Into<String>) -> Self {
+        self.discriminator = Some(property.into());
+        self
+    }
+
+    pub fn discriminator_value(mut self, value: impl Into<String>) -> Self {
+        self.x_ms_discriminator_value = Some(value.into());
+        self
+    }
+
+    pub fn xml(mut self, xml: Xml) -> Self {
+        self.xml = Some(xml);
+        self
+    }
+
+    pub fn read_only(mut self) -> Self {
+        self.read_only = Some(true);
+        self
+    }
+
+    pub fn nullable(mut self) -> Self {
+        self.x_nullable = Some(true);
+        self
+    }
+
+    pub fn secret(mut self) -> Self {
+        self.x_ms_secret = Some(true);
+        self
+    }
+
+    pub fn azure_resource(mut self) -> Self {
+        self.x_ms_azure_resource = Some(true);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn builds_documents() {
+        let spec = OpenAPI::new("Pets", "2020-01-01")
+            .host("pets.example.com")
+            .scheme(Scheme::Https)
+            .definition(
+                "Pet",
+                Schema::object()
+                    .property("id", Schema::string().read_only())
+                    .property("tags", Schema::array(Schema::string()))
+                    .property("owner", Schema::reference("Owner"))
+                    .required("id")
+                    .required("id"),
+            )
+            .parameter("ApiVersion", Parameter::query("api-version", DataType::String).required())
+            .operation(
+                "/pets/{petId}",
+                Method::Get,
+                Operation::new("Pets_Get")
+                    .parameter(Parameter::path("petId"))
+                    .parameter(Parameter::reference("ApiVersion"))
+                    .response(StatusCode::Code(200), Response::new("OK").schema(Schema::reference("Pet"))),
+            )
+            .operation(
+                "/pets/{petId}",
+                Method::Delete,
+                Operation::new("Pets_Delete").response(StatusCode::Code(204), Response::new("No Content")),
+            );
+        assert_eq!(
+            serde_json::to_value(&spec).unwrap(),
+            json!({
+                "swagger": "2.0",
+                "info": { "title": "Pets", "version": "2020-01-01" },
+                "host": "pets.example.com",
+                "schemes": ["https"],
+                "paths": {
+                    "/pets/{petId}": {
+                        "get": {
+                            "operationId": "Pets_Get",
+                            "responses": { "200": { "description": "OK", "schema": { "$ref": "#/definitions/Pet" } } },
+                            "parameters": [
+                                { "type": "string", "name": "petId", "in": "path", "required": true },
+                                { "$ref": "#/parameters/ApiVersion" }
+                            ]
+                        },
+                        "delete": { "operationId": "Pets_Delete", "responses": { "204": { "description": "No Content" } } }
+                    }
+                },
+                "definitions": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["id"],
+                        "properties": {
+                            "id": { "type": "string", "readOnly": true },
+                            "tags": { "type": "array", "items": { "type": "string" } },
+                            "owner": { "$ref": "#/definitions/Owner" }
+                        }
+                    }
+                },
+                "parameters": {
+                    "ApiVersion": { "type": "string", "name": "api-version", "in": "query", "required": true }
+                }
+            })
+        );
+    }
+}
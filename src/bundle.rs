@@ -0,0 +1,180 @@
+//! Bundles a document with the definitions and parameters it references in other files
+
+use crate::*;
+use indexmap::IndexMap;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// Reads a document and copies the definitions and parameters it references from other files into it,
+/// so that those references become local. References to example files are kept as they are.
+/// When a copied name is already used by something else, a numeric suffix is added to it.
+pub fn bundle(path: impl AsRef<Path>) -> Result<OpenAPI> {
+    let path = path.as_ref();
+    let mut spec = read_spec(path)?;
+    let mut bundler = Bundler {
+        root: path.canonicalize().map_err(|source| Error::Io {
+            path: path.to_owned(),
+            source,
+        })?,
+        dir: path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        files: HashMap::new(),
+        names: HashMap::new(),
+        definitions: IndexMap::new(),
+        parameters: IndexMap::new(),
+    };
+    loop {
+        let mut references = Vec::new();
+        spec.walk_references(|_pointer, reference| references.push(reference.clone()));
+        let replacements = references
+            .iter()
+            .map(|reference| bundler.localize(&spec, reference))
+            .collect::<Result<Vec<_>>>()?;
+        if replacements.iter().all(Option::is_none) {
+            return Ok(spec);
+        }
+        for (reference, replacement) in spec.references_mut().into_iter().zip(replacements) {
+            if let Some(replacement) = replacement {
+                *reference = replacement;
+            }
+        }
+        spec.definitions.extend(bundler.definitions.drain(..));
+        spec.parameters.extend(bundler.parameters.drain(..));
+    }
+}
+
+struct Bundler {
+    /// the canonical path of the document being bundled
+    root: PathBuf,
+    /// the directory of the document being bundled
+    dir: PathBuf,
+    files: HashMap<PathBuf, OpenAPI>,
+    /// local names by file, section and name
+    names: HashMap<(PathBuf, String, String), String>,
+    /// copies waiting to be added to the document
+    definitions: IndexMap<String, ReferenceOr<Schema>>,
+    parameters: IndexMap<String, Parameter>,
+}
+
+impl Bundler {
+    /// Returns the local reference to use instead, if the reference is to a definition or parameter in another file.
+    fn localize(&mut self, spec: &OpenAPI, reference: &Reference) -> Result<Option<Reference>> {
+        let (file, section, name) = match (&reference.file, reference.path.as_slice(), &reference.name) {
+            (Some(file), [section], Some(name)) if section == "definitions" || section == "parameters" => (file, section, name),
+            _ => return Ok(None),
+        };
+        let path = self.dir.join(file);
+        let path = path.canonicalize().map_err(|source| Error::Io { path, source })?;
+        if path == self.root {
+            return Ok(Some(Reference::local(section, name)));
+        }
+        let key = (path.clone(), section.clone(), name.clone());
+        if let Some(local_name) = self.names.get(&key) {
+            return Ok(Some(Reference::local(section, local_name)));
+        }
+
+        if !self.files.contains_key(&path) {
+            let source = read_spec(&path)?;
+            self.files.insert(path.clone(), source);
+        }
+        let source = &self.files[&path];
+        let unresolved = || Error::UnresolvedReference {
+            path: path.clone(),
+            reference: reference.clone(),
+        };
+        let mut copy = OpenAPI::default();
+        if section == "definitions" {
+            let schema = source.definitions.get(name).ok_or_else(unresolved)?;
+            copy.definitions.insert(name.clone(), schema.clone());
+        } else {
+            let parameter = source.parameters.get(name).ok_or_else(unresolved)?;
+            copy.parameters.insert(name.clone(), parameter.clone());
+        }
+        // the references of the copy are made relative to the directory of the document being bundled
+        let source_dir = path.parent().unwrap_or(&path);
+        for reference in copy.references_mut() {
+            let file = match &reference.file {
+                Some(file) => source_dir.join(file),
+                None => path.clone(),
+            };
+            reference.file = Some(file.to_string_lossy().into_owned());
+        }
+
+        let taken = |local_name: &String| {
+            if section == "definitions" {
+                spec.definitions.contains_key(local_name) || self.definitions.contains_key(local_name)
+            } else {
+                spec.parameters.contains_key(local_name) || self.parameters.contains_key(local_name)
+            }
+        };
+        let mut local_name = name.clone();
+        let mut suffix = 1;
+        while taken(&local_name) {
+            suffix += 1;
+            local_name = format!("{}{}", name, suffix);
+        }
+        self.definitions
+            .extend(copy.definitions.into_iter().map(|(_, schema)| (local_name.clone(), schema)));
+        self.parameters
+            .extend(copy.parameters.into_iter().map(|(_, parameter)| (local_name.clone(), parameter)));
+        self.names.insert(key, local_name.clone());
+        Ok(Some(Reference::local(section, &local_name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::{env, fs};
+
+    #[test]
+    fn copies_external_definitions() {
+        let dir = env::temp_dir().join(format!("autorust_openapi_bundle_{}", std::process::id()));
+        fs::create_dir_all(dir.join("common")).unwrap();
+        let root = json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": {},
+            "definitions": {
+                "Error": { "type": "string" },
+                "Cloud": {
+                    "properties": {
+                        "error": { "$ref": "common/types.json#/definitions/Error" },
+                        "resource": { "$ref": "./common/types.json#/definitions/Resource" }
+                    }
+                }
+            }
+        });
+        let types = json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": {},
+            "definitions": {
+                "Error": { "properties": { "code": { "type": "string" } } },
+                "Resource": { "properties": { "error": { "$ref": "#/definitions/Error" } } }
+            }
+        });
+        fs::write(dir.join("cloud.json"), root.to_string()).unwrap();
+        fs::write(dir.join("common/types.json"), types.to_string()).unwrap();
+
+        let spec = bundle(dir.join("cloud.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let spec = serde_json::to_value(spec).unwrap();
+        assert_eq!(
+            spec["definitions"],
+            json!({
+                "Error": { "type": "string" },
+                "Cloud": {
+                    "properties": {
+                        "error": { "$ref": "#/definitions/Error2" },
+                        "resource": { "$ref": "#/definitions/Resource" }
+                    }
+                },
+                "Error2": { "properties": { "code": { "type": "string" } } },
+                "Resource": { "properties": { "error": { "$ref": "#/definitions/Error2" } } }
+            })
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Caches documents so that files referenced by many others, such as common-types, are parsed once
+
+use crate::*;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+type Slot = Arc<Mutex<Option<Arc<OpenAPI>>>>;
+
+/// A document read along with the documents it references.
+#[derive(Debug)]
+pub struct LoadedDocument {
+    pub root: Arc<OpenAPI>,
+    /// The referenced documents that failed to read. The references to them are left as they are.
+    pub failures: Vec<Error>,
+}
+
+/// A thread-safe cache of documents, keyed by canonical path.
+/// Documents that fail to read are not cached, so reading them again returns the error again.
+#[derive(Debug, Default)]
+pub struct DocumentCache {
+    documents: Mutex<HashMap<PathBuf, Slot>>,
+    reads: AtomicUsize,
+}
+
+impl DocumentCache {
+    pub fn new() -> Self {
+        DocumentCache::default()
+    }
+
+    /// Reads a document, or returns the one already read from the same file.
+    /// When several threads ask for the same file at once, it is read by one while the others wait.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<Arc<OpenAPI>> {
+        let path = canonicalize(path.as_ref())?;
+        let slot = self
+            .documents
+            .lock()
+            .expect("not poisoned")
+            .entry(path.clone())
+            .or_default()
+            .clone();
+        let mut slot = slot.lock().expect("not poisoned");
+        if let Some(spec) = &*slot {
+            return Ok(spec.clone());
+        }
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        let spec = Arc::new(read_spec(&path)?);
+        *slot = Some(spec.clone());
+        Ok(spec)
+    }
+
+    /// Reads a document along with the documents it references, directly or indirectly.
+    /// References to files without a JSON Pointer, such as examples, are not followed.
+    /// Only failing to read the document itself is an error; referenced documents that fail are reported with it.
+    pub fn load_with_references(&self, path: impl AsRef<Path>) -> Result<LoadedDocument> {
+        let path = canonicalize(path.as_ref())?;
+        let root = self.load(&path)?;
+        let mut failures = Vec::new();
+        let mut visited = vec![path.clone()];
+        let mut pending = vec![(path, root.clone())];
+        while let Some((path, spec)) = pending.pop() {
+            let dir = path.parent().unwrap_or(&path).to_owned();
+            let mut files = Vec::new();
+            spec.walk_references(|_pointer, reference| {
+                if let Some(file) = &reference.file {
+                    if !reference.path.is_empty() && !files.contains(file) {
+                        files.push(file.clone());
+                    }
+                }
+            });
+            for file in files {
+                let path = dir.join(file);
+                let path = match canonicalize(&path) {
+                    Ok(path) => path,
+                    Err(err) => {
+                        if !visited.contains(&path) {
+                            visited.push(path);
+                            failures.push(err);
+                        }
+                        continue;
+                    }
+                };
+                if !visited.contains(&path) {
+                    visited.push(path.clone());
+                    match self.load(&path) {
+                        Ok(spec) => pending.push((path, spec)),
+                        Err(err) => failures.push(err),
+                    }
+                }
+            }
+        }
+        Ok(LoadedDocument { root, failures })
+    }
+
+    /// Reads documents along with the documents they reference, using the given number of threads.
+    /// The results are in the same order as the paths.
+    pub fn load_all<P: AsRef<Path> + Sync>(&self, paths: &[P], threads: usize) -> Vec<Result<LoadedDocument>> {
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<(usize, Result<LoadedDocument>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.max(1))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            match paths.get(index) {
+                                Some(path) => results.push((index, self.load_with_references(path))),
+                                None => return results,
+                            }
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("the worker does not panic"))
+                .collect()
+        });
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// The number of documents cached.
+    pub fn len(&self) -> usize {
+        let documents = self.documents.lock().expect("not poisoned");
+        documents
+            .values()
+            .filter(|slot| slot.lock().expect("not poisoned").is_some())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of times a file has been read and parsed, including those that failed.
+    pub fn reads(&self) -> usize {
+        self.reads.load(Ordering::Relaxed)
+    }
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf> {
+    path.canonicalize().map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::{env, fs};
+
+    #[test]
+    fn reads_shared_documents_once() {
+        let dir = env::temp_dir().join(format!("autorust_openapi_cache_{}", std::process::id()));
+        fs::create_dir_all(dir.join("common")).unwrap();
+        for name in &["a", "b"] {
+            let spec = json!({
+                "swagger": "2.0",
+                "info": {},
+                "paths": {},
+                "definitions": { "Resource": { "$ref": "./common/types.json#/definitions/Resource" } }
+            });
+            fs::write(dir.join(format!("{}.json", name)), spec.to_string()).unwrap();
+        }
+        let types = json!({ "swagger": "2.0", "info": {}, "paths": {}, "definitions": { "Resource": {} } });
+        fs::write(dir.join("common/types.json"), types.to_string()).unwrap();
+
+        let cache = DocumentCache::new();
+        let mut paths = vec![dir.join("a.json"), dir.join("missing.json"), dir.join("b.json")];
+        for _ in 0..8 {
+            paths.push(dir.join("a.json"));
+            paths.push(dir.join("b.json"));
+        }
+        let results = cache.load_all(&paths, 8);
+        let types = cache.load(dir.join("common/../common/types.json")).unwrap();
+        let again = cache.load(dir.join("common/types.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2..].iter().all(Result::is_ok));
+        assert_eq!(cache.len(), 3);
+        // a.json, b.json and common/types.json, each read once by whichever thread got there first
+        assert_eq!(cache.reads(), 3);
+        assert!(Arc::ptr_eq(&types, &again));
+    }
+
+    #[test]
+    fn reports_failed_references_with_the_root() {
+        let dir = env::temp_dir().join(format!("autorust_openapi_cache_missing_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let spec = json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": {},
+            "definitions": {
+                "Resource": { "$ref": "./missing.json#/definitions/Resource" },
+                "Other": { "$ref": "./missing.json#/definitions/Other" }
+            }
+        });
+        fs::write(dir.join("a.json"), spec.to_string()).unwrap();
+
+        let loaded = DocumentCache::new().load_with_references(dir.join("a.json"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.root.definitions.len(), 2);
+        assert_eq!(loaded.failures.len(), 1);
+        assert!(matches!(&loaded.failures[0], Error::Io { path, .. } if path.ends_with("missing.json")));
+    }
+}
@@ -0,0 +1,185 @@
+//! Catalogs the documents of a checkout laid out like azure-rest-api-specs
+//! `specification/<service>/<resource-manager|data-plane>/<Provider>/<stable|preview>/<api-version>/*.json`
+//! https://github.com/Azure/azure-rest-api-specs
+
+use crate::*;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Plane {
+    ResourceManager,
+    DataPlane,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stability {
+    Stable,
+    Preview,
+}
+
+/// Where a document is within the `specification` directory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecLocation {
+    pub service: String,
+    pub plane: Plane,
+    /// The directories between the plane and the stability, such as `Microsoft.AVS`, joined with `/`.
+    pub provider: String,
+    pub stability: Stability,
+    pub api_version: String,
+}
+
+impl SpecLocation {
+    /// Parses a path relative to the `specification` directory.
+    /// Returns `None` for documents that do not follow the layout, such as those in `common-types` or `examples`.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let names: Vec<&str> = path.as_ref().iter().map(|name| name.to_str()).collect::<Option<_>>()?;
+        let (service, plane, rest) = match names.as_slice() {
+            [service, plane, rest @ ..] => (service, plane, rest),
+            _ => return None,
+        };
+        let plane = match *plane {
+            "resource-manager" => Plane::ResourceManager,
+            "data-plane" => Plane::DataPlane,
+            _ => return None,
+        };
+        let (provider, stability, api_version) = match rest {
+            [provider @ .., stability, api_version, _file] if !provider.is_empty() => (provider, stability, api_version),
+            _ => return None,
+        };
+        let stability = match *stability {
+            "stable" => Stability::Stable,
+            "preview" => Stability::Preview,
+            _ => return None,
+        };
+        Some(SpecLocation {
+            service: (*service).to_owned(),
+            plane,
+            provider: provider.join("/"),
+            stability,
+            api_version: (*api_version).to_owned(),
+        })
+    }
+}
+
+/// A document that was read successfully.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogEntry {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub location: SpecLocation,
+    pub operations: usize,
+}
+
+/// The documents of a checkout, along with the ones that could not be read.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    pub entries: Vec<CatalogEntry>,
+    pub failures: Vec<Error>,
+}
+
+/// Lists the documents that follow the layout, sorted by path.
+/// The root may be the checkout or its `specification` directory.
+pub fn spec_files(root: impl AsRef<Path>) -> Result<Vec<(PathBuf, SpecLocation)>> {
+    let root = specification_dir(root.as_ref());
+    let mut files = Vec::new();
+    let mut pending = vec![root.clone()];
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir).map_err(|source| Error::Io { path: dir.clone(), source })?;
+        for entry in entries {
+            let path = entry.map_err(|source| Error::Io { path: dir.clone(), source })?.path();
+            if path.is_dir() {
+                if path.file_name().is_some_and(|name| name != "examples") {
+                    pending.push(path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                let relative = path.strip_prefix(&root).expect("walked from the root");
+                if let Some(location) = SpecLocation::from_path(relative) {
+                    files.push((path, location));
+                }
+            }
+        }
+    }
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(files)
+}
+
+/// Reads every document that follows the layout. Documents that fail to read are collected instead of stopping the walk.
+pub fn catalog(root: impl AsRef<Path>) -> Result<Catalog> {
+    let mut catalog = Catalog::default();
+    for (path, location) in spec_files(root)? {
+        match read_spec(&path) {
+            Ok(spec) => catalog.entries.push(CatalogEntry {
+                operations: spec.operations().count(),
+                path,
+                location,
+            }),
+            Err(err) => catalog.failures.push(err),
+        }
+    }
+    Ok(catalog)
+}
+
+fn specification_dir(root: &Path) -> PathBuf {
+    let specification = root.join("specification");
+    if specification.is_dir() {
+        specification
+    } else {
+        root.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::env;
+
+    #[test]
+    fn parses_locations() {
+        assert_eq!(
+            SpecLocation::from_path("batch/data-plane/Microsoft.Batch/stable/2020-03-01.11.0/BatchService.json"),
+            Some(SpecLocation {
+                service: "batch".to_owned(),
+                plane: Plane::DataPlane,
+                provider: "Microsoft.Batch".to_owned(),
+                stability: Stability::Stable,
+                api_version: "2020-03-01.11.0".to_owned(),
+            })
+        );
+        assert_eq!(SpecLocation::from_path("security/resource-manager/common/v1/types.json"), None);
+        assert_eq!(
+            SpecLocation::from_path("vmware/resource-manager/Microsoft.AVS/stable/2020-03-20/examples/Clouds_Get.json"),
+            None
+        );
+    }
+
+    #[test]
+    fn collects_failures() {
+        let root = env::temp_dir().join(format!("autorust_openapi_catalog_{}", std::process::id()));
+        let dir = root.join("specification/vmware/resource-manager/Microsoft.AVS/preview/2019-08-09-preview");
+        fs::create_dir_all(dir.join("examples")).unwrap();
+        let spec = json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": { "/clouds": { "get": { "responses": {} }, "put": { "responses": {} } } }
+        });
+        fs::write(dir.join("vmware.json"), spec.to_string()).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        fs::write(dir.join("examples/Clouds_Get.json"), "{}").unwrap();
+
+        let catalog = catalog(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(catalog.entries.len(), 1);
+        assert_eq!(catalog.entries[0].operations, 2);
+        assert_eq!(catalog.entries[0].location.stability, Stability::Preview);
+        assert_eq!(catalog.failures.len(), 1);
+    }
+}
@@ -0,0 +1,185 @@
+//! Compares two documents
+
+use crate::*;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::{fmt, hash::Hash};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A difference between two documents, located by a JSON Pointer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub pointer: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Modified => '~',
+        };
+        write!(f, "{} {}", sign, self.pointer)
+    }
+}
+
+/// Lists the operations, definitions, parameters, responses and security definitions that differ.
+/// Any other difference is reported on its top level field, such as `/info`.
+pub fn diff(old: &OpenAPI, new: &OpenAPI) -> Vec<Change> {
+    let mut changes = Vec::new();
+    macro_rules! field {
+        ($name:literal, $field:ident) => {
+            if old.$field != new.$field {
+                changes.push(Change {
+                    kind: ChangeKind::Modified,
+                    pointer: concat!("/", $name).to_owned(),
+                });
+            }
+        };
+    }
+    field!("swagger", swagger);
+    field!("info", info);
+    field!("host", host);
+    field!("basePath", base_path);
+    field!("schemes", schemes);
+    field!("consumes", consumes);
+    field!("produces", produces);
+    diff_paths("/paths", &old.paths, &new.paths, &mut changes);
+    diff_paths("/x-ms-paths", &old.x_ms_paths, &new.x_ms_paths, &mut changes);
+    diff_map("/definitions", &old.definitions, &new.definitions, &mut changes);
+    diff_map("/parameters", &old.parameters, &new.parameters, &mut changes);
+    diff_map("/responses", &old.responses, &new.responses, &mut changes);
+    diff_map(
+        "/securityDefinitions",
+        &old.security_definitions,
+        &new.security_definitions,
+        &mut changes,
+    );
+    field!("security", security);
+    field!("tags", tags);
+    field!("externalDocs", external_docs);
+    field!("x-ms-parameterized-host", x_ms_parameterized_host);
+    changes
+}
+
+fn diff_map<K, V>(prefix: &str, old: &IndexMap<K, V>, new: &IndexMap<K, V>, changes: &mut Vec<Change>)
+where
+    K: Hash + Eq + ToString,
+    V: PartialEq,
+{
+    let pointer = |name: &K| format!("{}/{}", prefix, escape_pointer_token(&name.to_string()));
+    for (name, old_value) in old {
+        match new.get(name) {
+            None => changes.push(Change {
+                kind: ChangeKind::Removed,
+                pointer: pointer(name),
+            }),
+            Some(new_value) if new_value != old_value => changes.push(Change {
+                kind: ChangeKind::Modified,
+                pointer: pointer(name),
+            }),
+            Some(_) => {}
+        }
+    }
+    for name in new.keys().filter(|name| !old.contains_key(*name)) {
+        changes.push(Change {
+            kind: ChangeKind::Added,
+            pointer: pointer(name),
+        });
+    }
+}
+
+/// Compares path items by operation, so that a single changed operation is reported on its own.
+fn diff_paths(
+    prefix: &str,
+    old: &IndexMap<String, ReferenceOr<PathItem>>,
+    new: &IndexMap<String, ReferenceOr<PathItem>>,
+    changes: &mut Vec<Change>,
+) {
+    for (path, old_item) in old {
+        let pointer = format!("{}/{}", prefix, escape_pointer_token(path));
+        match (old_item, new.get(path)) {
+            (_, None) => changes.push(Change {
+                kind: ChangeKind::Removed,
+                pointer,
+            }),
+            (ReferenceOr::Item(old_item), Some(ReferenceOr::Item(new_item))) => {
+                if old_item.parameters != new_item.parameters {
+                    changes.push(Change {
+                        kind: ChangeKind::Modified,
+                        pointer: format!("{}/parameters", pointer),
+                    });
+                }
+                let old_methods: IndexMap<_, _> = old_item.methods().collect();
+                let new_methods: IndexMap<_, _> = new_item.methods().collect();
+                diff_map(&pointer, &old_methods, &new_methods, changes);
+            }
+            (old_item, Some(new_item)) if old_item != new_item => changes.push(Change {
+                kind: ChangeKind::Modified,
+                pointer,
+            }),
+            _ => {}
+        }
+    }
+    for path in new.keys().filter(|path| !old.contains_key(*path)) {
+        changes.push(Change {
+            kind: ChangeKind::Added,
+            pointer: format!("{}/{}", prefix, escape_pointer_token(path)),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_operation_and_definition_changes() {
+        let old: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": { "version": "1" },
+            "paths": {
+                "/a": { "get": { "responses": {} }, "put": { "responses": {} } },
+                "/b": { "get": { "responses": {} } }
+            },
+            "definitions": { "A": { "type": "string" }, "B": { "type": "string" } }
+        }))
+        .unwrap();
+        let new: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": { "version": "2" },
+            "paths": {
+                "/a": { "get": { "summary": "changed", "responses": {} }, "delete": { "responses": {} } },
+                "/c": { "get": { "responses": {} } }
+            },
+            "definitions": { "A": { "type": "integer" }, "B": { "type": "string" } }
+        }))
+        .unwrap();
+        let changes: Vec<String> = diff(&old, &new).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            changes,
+            vec![
+                "~ /info",
+                "~ /paths/~1a/get",
+                "- /paths/~1a/put",
+                "+ /paths/~1a/delete",
+                "- /paths/~1b",
+                "+ /paths/~1c",
+                "~ /definitions/A",
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&diff(&old, &new)[2]).unwrap(),
+            json!({ "kind": "removed", "pointer": "/paths/~1a/put" })
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Enums combining `enum` values with `x-ms-enum` metadata
+//! https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-enum
+
+use crate::*;
+use indexmap::IndexMap;
+use serde_json::Value;
+
+/// An enum resolved from the `enum` values of a schema and its `x-ms-enum`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Enum<'a> {
+    /// The `x-ms-enum` name, if any.
+    pub name: Option<&'a str>,
+    /// When `true`, the enum is extensible and other values may be sent on the wire.
+    /// `modelAsString` is `false` when not specified.
+    pub model_as_string: bool,
+    pub values: Vec<EnumValue<'a>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumValue<'a> {
+    /// The value as sent on the wire.
+    pub value: &'a Value,
+    pub description: Option<&'a str>,
+    /// The name to use in client code, from `x-ms-enum` `values`.
+    pub name: Option<&'a str>,
+}
+
+impl SchemaCommon {
+    /// Combines `enum` with `x-ms-enum`, or returns `None` if this schema is not an enum.
+    /// The values are ordered as in `enum`, followed by any `x-ms-enum` values missing from it.
+    pub fn enum_model(&self) -> Option<Enum<'_>> {
+        if self.enum_.is_empty() && self.x_ms_enum.is_none() {
+            return None;
+        }
+        let ms_values: &[MsEnumValue] = self.x_ms_enum.as_ref().map(|ms| ms.values.as_slice()).unwrap_or_default();
+        let mut values: Vec<EnumValue> = self
+            .enum_
+            .iter()
+            .map(|value| {
+                let ms_value = ms_values.iter().find(|ms_value| &ms_value.value == value);
+                EnumValue {
+                    value,
+                    description: ms_value.and_then(|ms_value| ms_value.description.as_deref()),
+                    name: ms_value.and_then(|ms_value| ms_value.name.as_deref()),
+                }
+            })
+            .collect();
+        for ms_value in ms_values {
+            if !self.enum_.contains(&ms_value.value) {
+                values.push(EnumValue {
+                    value: &ms_value.value,
+                    description: ms_value.description.as_deref(),
+                    name: ms_value.name.as_deref(),
+                });
+            }
+        }
+        Some(Enum {
+            name: self.x_ms_enum.as_ref().map(|ms| ms.name.as_str()),
+            model_as_string: self.x_ms_enum.as_ref().and_then(|ms| ms.model_as_string).unwrap_or(false),
+            values,
+        })
+    }
+}
+
+impl<'a> Enum<'a> {
+    /// Returns true if values other than the listed ones may be sent on the wire.
+    pub fn is_extensible(&self) -> bool {
+        self.model_as_string
+    }
+
+    /// Returns true if both enums have the same set of wire values, in any order, and the same extensibility.
+    pub fn same_values(&self, other: &Enum) -> bool {
+        fn contains_all(a: &Enum, b: &Enum) -> bool {
+            a.values.iter().all(|a| b.values.iter().any(|b| a.value == b.value))
+        }
+        self.model_as_string == other.model_as_string && contains_all(self, other) && contains_all(other, self)
+    }
+}
+
+/// All of the named enums in a document.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct EnumRegistry<'a> {
+    /// The enums by `x-ms-enum` name, as first seen in the document.
+    pub enums: IndexMap<&'a str, RegisteredEnum<'a>>,
+    /// Enums sharing a name with an earlier one, but not its values.
+    pub conflicts: Vec<EnumConflict<'a>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisteredEnum<'a> {
+    pub model: Enum<'a>,
+    /// JSON Pointers to every schema declaring this enum.
+    pub pointers: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumConflict<'a> {
+    pub name: &'a str,
+    /// JSON Pointer to the schema that conflicts.
+    pub pointer: String,
+    /// JSON Pointer to the first schema declaring the enum.
+    pub existing: String,
+    pub model: Enum<'a>,
+}
+
+impl OpenAPI {
+    /// Collects the enums declared with an `x-ms-enum` name anywhere in the document.
+    pub fn enum_registry(&self) -> EnumRegistry<'_> {
+        let mut registry = EnumRegistry::default();
+        self.walk_schemas(|pointer, common| {
+            let model = match common.enum_model() {
+                Some(model) => model,
+                None => return,
+            };
+            let name = match model.name {
+                Some(name) => name,
+                None => return,
+            };
+            match registry.enums.get_mut(name) {
+                None => {
+                    registry.enums.insert(
+                        name,
+                        RegisteredEnum {
+                            model,
+                            pointers: vec![pointer.to_owned()],
+                        },
+                    );
+                }
+                Some(registered) if registered.model.same_values(&model) => registered.pointers.push(pointer.to_owned()),
+                Some(registered) => registry.conflicts.push(EnumConflict {
+                    name,
+                    pointer: pointer.to_owned(),
+                    existing: registered.pointers[0].clone(),
+                    model,
+                }),
+            }
+        });
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn combines_enum_with_x_ms_enum() {
+        let schema: Schema = serde_json::from_value(json!({
+            "type": "string",
+            "enum": ["Succeeded", "Failed"],
+            "x-ms-enum": {
+                "name": "ProvisioningState",
+                "modelAsString": true,
+                "values": [{ "value": "Failed", "description": "It failed", "name": "Failure" }]
+            }
+        }))
+        .unwrap();
+        let model = schema.common.enum_model().unwrap();
+        assert_eq!(model.name, Some("ProvisioningState"));
+        assert!(model.is_extensible());
+        assert_eq!(model.values.len(), 2);
+        assert_eq!(model.values[0].value, &json!("Succeeded"));
+        assert_eq!(model.values[0].name, None);
+        assert_eq!(model.values[1].name, Some("Failure"));
+        assert_eq!(model.values[1].description, Some("It failed"));
+    }
+
+    #[test]
+    fn registry_detects_conflicts() {
+        let spec: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": {},
+            "definitions": {
+                "A": { "properties": { "state": { "type": "string", "enum": ["On", "Off"], "x-ms-enum": { "name": "State" } } } },
+                "B": { "properties": { "state": { "type": "string", "enum": ["Off", "On"], "x-ms-enum": { "name": "State" } } } },
+                "C": { "properties": { "state": { "type": "string", "enum": ["On"], "x-ms-enum": { "name": "State" } } } }
+            }
+        }))
+        .unwrap();
+        let registry = spec.enum_registry();
+        assert_eq!(registry.enums.len(), 1);
+        assert_eq!(
+            registry.enums["State"].pointers,
+            vec!["/definitions/A/properties/state", "/definitions/B/properties/state"]
+        );
+        assert_eq!(registry.conflicts.len(), 1);
+        assert_eq!(registry.conflicts[0].pointer, "/definitions/C/properties/state");
+        assert_eq!(registry.conflicts[0].existing, "/definitions/A/properties/state");
+    }
+
+    #[test]
+    fn compares_values_as_sets() {
+        let schema = |values: Value| -> Schema { serde_json::from_value(json!({ "type": "string", "enum": values })).unwrap() };
+        let on_on = schema(json!(["On", "On"]));
+        let on_off = schema(json!(["On", "Off"]));
+        let off_on = schema(json!(["Off", "On"]));
+        let on = schema(json!(["On"]));
+        let same = |a: &Schema, b: &Schema| a.common.enum_model().unwrap().same_values(&b.common.enum_model().unwrap());
+        assert!(same(&on_off, &off_on));
+        assert!(!same(&on_on, &on_off));
+        assert!(!same(&on_off, &on_on));
+        assert!(same(&on_on, &on));
+    }
+}
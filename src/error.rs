@@ -0,0 +1,60 @@
+#[cfg(feature = "yaml")]
+use crate::ReadmeError;
+use crate::Reference;
+use std::{fmt, io, path::PathBuf};
+
+#[derive(Debug)]
+pub enum Error {
+    /// the file could not be read or written
+    Io { path: PathBuf, source: io::Error },
+    /// the file is not a valid JSON OpenAPI document
+    Json { path: PathBuf, source: serde_json::Error },
+    /// the file is not a valid YAML OpenAPI document
+    #[cfg(feature = "yaml")]
+    Yaml { path: PathBuf, source: serde_yaml::Error },
+    /// the file is not a valid AutoRest readme.md
+    #[cfg(feature = "yaml")]
+    Readme { path: PathBuf, source: ReadmeError },
+    /// the reference does not point to anything in the file
+    UnresolvedReference { path: PathBuf, reference: Reference },
+    /// the `x-ms-examples` entry points within the document, instead of to an example in a file
+    UnsupportedExample { name: String },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Json { path, source } => write!(f, "{}: {}", path.display(), source),
+            #[cfg(feature = "yaml")]
+            Error::Yaml { path, source } => write!(f, "{}: {}", path.display(), source),
+            #[cfg(feature = "yaml")]
+            Error::Readme { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::UnresolvedReference { path, reference } => {
+                let reference = serde_json::to_string(reference).unwrap_or_default();
+                write!(f, "{}: unable to resolve {}", path.display(), reference)
+            }
+            Error::UnsupportedExample { name } => write!(
+                f,
+                "x-ms-examples '{}' points within the document instead of to an example file",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Json { source, .. } => Some(source),
+            #[cfg(feature = "yaml")]
+            Error::Yaml { source, .. } => Some(source),
+            #[cfg(feature = "yaml")]
+            Error::Readme { source, .. } => Some(source),
+            Error::UnresolvedReference { .. } | Error::UnsupportedExample { .. } => None,
+        }
+    }
+}
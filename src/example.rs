@@ -1,17 +1,245 @@
+use crate::{Error, Operation, Reference, ReferenceOr, Result, StatusCode};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 /// This is the root document object for an example.
 /// https://github.com/Azure/azure-rest-api-specs/blob/master/documentation/x-ms-examples.md
 /// https://github.com/Azure/autorest/blob/main/packages/libs/autorest-schemas/example-schema.json
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct Example {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(rename = "operationId", skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// The values of the parameters by name, including the body parameter.
     pub parameters: IndexMap<String, serde_json::Value>,
-    pub responses: IndexMap<String, Response>,
+    pub responses: IndexMap<StatusCode, Response>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct Response {
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub headers: IndexMap<String, serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body: Option<serde_json::Value>,
 }
+
+/// Where an example of an operation comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExampleSource {
+    /// the `examples` of a Response Object, by MIME type
+    Inline { status_code: StatusCode, mime_type: String },
+    /// an example written within `x-ms-examples`
+    MsExample,
+    /// a file referenced by `x-ms-examples`
+    File(PathBuf),
+    /// an example within a file referenced by `x-ms-examples`, located by a JSON Pointer
+    Pointer { path: PathBuf, pointer: String },
+}
+
+/// A request along with the responses to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperationExample {
+    /// The `x-ms-examples` name, or the status code and MIME type of an inline example.
+    pub name: String,
+    pub source: ExampleSource,
+    pub example: Example,
+}
+
+impl Operation {
+    /// Collects the inline examples of the responses and reads the `x-ms-examples` files, relative to `dir`.
+    /// Inline examples only have a response, so their `parameters` are empty.
+    /// An `x-ms-examples` reference that points within the document, instead of to a file, is an error.
+    pub fn examples(&self, dir: impl AsRef<Path>) -> Result<Vec<OperationExample>> {
+        let mut examples = Vec::new();
+        for (status_code, response) in &self.responses {
+            for (mime_type, body) in &response.examples {
+                let mut example = Example::default();
+                example.responses.insert(
+                    status_code.clone(),
+                    Response {
+                        headers: IndexMap::new(),
+                        body: Some(body.clone()),
+                    },
+                );
+                examples.push(OperationExample {
+                    name: format!("{} {}", status_code, mime_type),
+                    source: ExampleSource::Inline {
+                        status_code: status_code.clone(),
+                        mime_type: mime_type.clone(),
+                    },
+                    example,
+                });
+            }
+        }
+        for (name, example) in &self.x_ms_examples {
+            let (reference, file) = match example {
+                ReferenceOr::Reference { reference, .. } => match &reference.file {
+                    Some(file) => (reference, file),
+                    None => return Err(Error::UnsupportedExample { name: name.clone() }),
+                },
+                ReferenceOr::Item(example) => {
+                    examples.push(OperationExample {
+                        name: name.clone(),
+                        source: ExampleSource::MsExample,
+                        example: example.clone(),
+                    });
+                    continue;
+                }
+            };
+            let path = dir.as_ref().join(file);
+            let tokens: Vec<&str> = reference.path.iter().chain(&reference.name).map(String::as_str).collect();
+            let (example, source) = if tokens.is_empty() {
+                (read_example(&path)?, ExampleSource::File(path))
+            } else {
+                let pointer = format!("/{}", tokens.join("/"));
+                (
+                    read_example_at(&path, &pointer, reference)?,
+                    ExampleSource::Pointer { path, pointer },
+                )
+            };
+            examples.push(OperationExample {
+                name: name.clone(),
+                example,
+                source,
+            });
+        }
+        Ok(examples)
+    }
+}
+
+/// Reads an example from a file.
+pub fn read_example(path: impl AsRef<Path>) -> Result<Example> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    serde_json::from_slice(&bytes).map_err(|source| Error::Json {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Reads the example at a JSON Pointer within a file.
+fn read_example_at(path: &Path, pointer: &str, reference: &Reference) -> Result<Example> {
+    let bytes = fs::read(path).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    let json_error = |source| Error::Json {
+        path: path.to_owned(),
+        source,
+    };
+    let mut value: serde_json::Value = serde_json::from_slice(&bytes).map_err(json_error)?;
+    match value.pointer_mut(pointer) {
+        Some(example) => serde_json::from_value(example.take()).map_err(json_error),
+        None => Err(Error::UnresolvedReference {
+            path: path.to_owned(),
+            reference: reference.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::env;
+
+    #[test]
+    fn collects_inline_and_file_examples() {
+        let dir = env::temp_dir().join(format!("autorust_openapi_example_{}", std::process::id()));
+        fs::create_dir_all(dir.join("examples")).unwrap();
+        let file = json!({
+            "title": "Get a pet",
+            "parameters": { "petId": "rex", "api-version": "2020-01-01" },
+            "responses": {
+                "200": { "headers": { "ETag": "\"1\"" }, "body": { "name": "rex" } },
+                "default": { "body": { "error": { "code": "NotFound" } } }
+            }
+        });
+        fs::write(dir.join("examples/Pets_Get.json"), file.to_string()).unwrap();
+        let operation: Operation = serde_json::from_value(json!({
+            "operationId": "Pets_Get",
+            "responses": {
+                "200": { "description": "OK", "examples": { "application/json": { "name": "tom" } } }
+            },
+            "x-ms-examples": { "Get a pet": { "$ref": "./examples/Pets_Get.json" } }
+        }))
+        .unwrap();
+
+        let examples = operation.examples(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].name, "200 application/json");
+        assert_eq!(
+            examples[0].example.responses[&StatusCode::Code(200)].body,
+            Some(json!({ "name": "tom" }))
+        );
+        assert_eq!(examples[1].name, "Get a pet");
+        let example = &examples[1].example;
+        assert_eq!(example.parameters["petId"], json!("rex"));
+        assert_eq!(example.responses[&StatusCode::Code(200)].headers["ETag"], json!("\"1\""));
+        assert!(example.responses.contains_key(&StatusCode::Default));
+        assert_eq!(serde_json::to_value(example).unwrap(), file);
+    }
+
+    #[test]
+    fn reads_examples_by_pointer_and_inline() {
+        let dir = env::temp_dir().join(format!("autorust_openapi_example_pointer_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = json!({ "Pets_Get": { "parameters": { "petId": "rex" }, "responses": { "200": { "body": {} } } } });
+        fs::write(dir.join("examples.json"), file.to_string()).unwrap();
+        let operation: Operation = serde_json::from_value(json!({
+            "responses": {},
+            "x-ms-examples": { "Get a pet": { "$ref": "./examples.json#/Pets_Get" } }
+        }))
+        .unwrap();
+        let examples = operation.examples(&dir);
+        let missing: Operation = serde_json::from_value(json!({
+            "responses": {},
+            "x-ms-examples": { "Missing": { "$ref": "./examples.json#/Pets_List" } }
+        }))
+        .unwrap();
+        let missing = missing.examples(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let examples = examples.unwrap();
+        assert_eq!(examples[0].example.parameters["petId"], json!("rex"));
+        assert_eq!(
+            examples[0].source,
+            ExampleSource::Pointer {
+                path: dir.join("./examples.json"),
+                pointer: "/Pets_Get".to_owned()
+            }
+        );
+        assert!(matches!(missing, Err(Error::UnresolvedReference { .. })));
+
+        let inline: Operation = serde_json::from_value(json!({
+            "responses": {},
+            "x-ms-examples": { "Inline": { "parameters": { "petId": "tom" }, "responses": { "200": { "body": { "name": "tom" } } } } }
+        }))
+        .unwrap();
+        let examples = inline.examples(&dir).unwrap();
+        assert_eq!(examples[0].name, "Inline");
+        assert_eq!(examples[0].source, ExampleSource::MsExample);
+        assert_eq!(examples[0].example.parameters["petId"], json!("tom"));
+        assert_eq!(
+            examples[0].example.responses[&StatusCode::Code(200)].body,
+            Some(json!({ "name": "tom" }))
+        );
+
+        let local: Operation =
+            serde_json::from_value(json!({ "responses": {}, "x-ms-examples": { "Local": { "$ref": "#/x-examples/Get" } } })).unwrap();
+        let err = local.examples(&dir).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "x-ms-examples 'Local' points within the document instead of to an example file"
+        );
+    }
+}
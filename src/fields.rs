@@ -0,0 +1,132 @@
+//! Deserializes objects one field at a time
+//! `#[serde(flatten)]` buffers the whole object before any field is read, so the types that share `SchemaCommon`
+//! read their fields through `ReadFields` instead. Errors in a field are prefixed with its name.
+
+use crate::*;
+use indexmap::IndexMap;
+use serde::{
+    de::{IgnoredAny, MapAccess, Visitor},
+    Deserialize,
+};
+use std::{fmt, marker::PhantomData};
+
+/// A type whose fields can be read as part of another object.
+pub(crate) trait ReadFields<'de>: Default {
+    /// Reads the value of `key` if it is one of the fields. Returns false, without reading the value, if it is not.
+    fn read_field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error>;
+}
+
+/// Reads the value of a field, naming it in the error.
+pub(crate) fn next_value<'de, T: Deserialize<'de>, A: MapAccess<'de>>(map: &mut A, key: &str) -> Result<T, A::Error> {
+    map.next_value()
+        .map_err(|err| if names_fields::<A::Error>() { err } else { with_field(key, err) })
+}
+
+/// Whether errors are already prefixed with the path to the failing field, as those of `serde_yaml` are.
+#[cfg_attr(not(feature = "yaml"), allow(clippy::extra_unused_type_parameters))]
+fn names_fields<E>() -> bool {
+    #[cfg(feature = "yaml")]
+    {
+        std::any::type_name::<E>() == std::any::type_name::<serde_yaml::Error>()
+    }
+    #[cfg(not(feature = "yaml"))]
+    {
+        false
+    }
+}
+
+/// Names the field in an error from deserializing its value.
+/// The position is dropped from the message, as the deserializer adds it again once the error leaves the object.
+pub(crate) fn with_field<E: serde::de::Error>(key: &str, err: impl fmt::Display) -> E {
+    let message = err.to_string();
+    E::custom(format_args!("{}: {}", key, without_position(&message)))
+}
+
+/// The message without the ` at line 1 column 2` that `serde_json` and `serde_yaml` end errors with.
+fn without_position(message: &str) -> &str {
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit());
+    if let Some((rest, column)) = message.rsplit_once(" column ") {
+        if let Some((rest, line)) = rest.rsplit_once(" at line ") {
+            if is_number(line) && is_number(column) {
+                return rest;
+            }
+        }
+    }
+    message
+}
+
+/// Deserializes a `ReadFields` type from an object, ignoring unknown fields.
+pub(crate) struct FieldsVisitor<T> {
+    expecting: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<T> FieldsVisitor<T> {
+    pub(crate) fn new(expecting: &'static str) -> Self {
+        FieldsVisitor {
+            expecting,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: ReadFields<'de>> Visitor<'de> for FieldsVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+        let mut value = T::default();
+        while let Some(key) = map.next_key::<BorrowedKey>()? {
+            if !value.read_field(&key.0, &mut map)? {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// A map whose entries are named in errors, such as the properties of a schema.
+pub(crate) struct NamedMap<V>(pub(crate) IndexMap<String, V>);
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for NamedMap<V> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(NamedMapVisitor(PhantomData))
+    }
+}
+
+struct NamedMapVisitor<V>(PhantomData<V>);
+
+impl<'de, V: Deserialize<'de>> Visitor<'de> for NamedMapVisitor<V> {
+    type Value = NamedMap<V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = IndexMap::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(key) = map.next_key::<String>()? {
+            let value = next_value(&mut map, &key)?;
+            entries.insert(key, value);
+        }
+        Ok(NamedMap(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_the_position_from_messages() {
+        assert_eq!(
+            without_position("unknown variant `strin` at line 1 column 64"),
+            "unknown variant `strin`"
+        );
+        assert_eq!(without_position("expected a column at line one"), "expected a column at line one");
+        assert_eq!(without_position("missing field `in`"), "missing field `in`");
+    }
+}
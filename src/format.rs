@@ -0,0 +1,517 @@
+//! Typed values of the `format` keyword and checks that values match them
+//! https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#data-types
+//! https://github.com/Azure/autorest/blob/main/docs/developer/architecture/AutoRest-extension-schemas.md
+
+use crate::SchemaCommon;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    convert::{Infallible, TryFrom},
+    fmt,
+    str::FromStr,
+    sync::Arc,
+};
+
+/// A `format` from Swagger or AutoRest. Any other format is `Custom`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum Format {
+    Int32,
+    Int64,
+    Float,
+    Double,
+    /// base64 encoded characters
+    Byte,
+    /// any sequence of octets
+    Binary,
+    /// `full-date` from RFC 3339
+    Date,
+    /// `date-time` from RFC 3339
+    DateTime,
+    Password,
+    Uuid,
+    /// an ISO 8601 duration, such as `PT5M`
+    Duration,
+    /// such as `Sun, 06 Nov 1994 08:49:37 GMT`
+    DateTimeRfc1123,
+    /// seconds since the Unix epoch
+    UnixTime,
+    /// base64url encoded characters, without padding
+    Base64Url,
+    /// an Azure Resource Manager resource id
+    ArmId,
+    Uri,
+    Url,
+    Decimal,
+    Custom(String),
+}
+
+impl Format {
+    /// The formats other than `Custom`.
+    pub const KNOWN: &'static [Format] = &[
+        Format::Int32,
+        Format::Int64,
+        Format::Float,
+        Format::Double,
+        Format::Byte,
+        Format::Binary,
+        Format::Date,
+        Format::DateTime,
+        Format::Password,
+        Format::Uuid,
+        Format::Duration,
+        Format::DateTimeRfc1123,
+        Format::UnixTime,
+        Format::Base64Url,
+        Format::ArmId,
+        Format::Uri,
+        Format::Url,
+        Format::Decimal,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Format::Int32 => "int32",
+            Format::Int64 => "int64",
+            Format::Float => "float",
+            Format::Double => "double",
+            Format::Byte => "byte",
+            Format::Binary => "binary",
+            Format::Date => "date",
+            Format::DateTime => "date-time",
+            Format::Password => "password",
+            Format::Uuid => "uuid",
+            Format::Duration => "duration",
+            Format::DateTimeRfc1123 => "date-time-rfc1123",
+            Format::UnixTime => "unixtime",
+            Format::Base64Url => "base64url",
+            Format::ArmId => "arm-id",
+            Format::Uri => "uri",
+            Format::Url => "url",
+            Format::Decimal => "decimal",
+            Format::Custom(format) => format,
+        }
+    }
+
+    /// Whether the value matches the format. Every value matches a `Custom` format.
+    pub fn is_valid(&self, value: &Value) -> bool {
+        match self {
+            Format::Int32 => value.as_i64().is_some_and(|n| i32::try_from(n).is_ok()),
+            Format::Int64 | Format::UnixTime => value.is_i64(),
+            Format::Float => value.as_f64().is_some_and(|n| n.abs() <= f32::MAX as f64),
+            Format::Double => value.is_number(),
+            Format::Decimal => value.is_number() || value.as_str().is_some_and(is_decimal),
+            Format::Custom(_) => true,
+            _ => value.as_str().is_some_and(|s| self.is_valid_str(s)),
+        }
+    }
+
+    fn is_valid_str(&self, s: &str) -> bool {
+        match self {
+            Format::Byte => is_base64(s, |c| c == b'+' || c == b'/', true),
+            Format::Base64Url => is_base64(s, |c| c == b'-' || c == b'_', false),
+            Format::Date => is_date(s),
+            Format::DateTime => is_date_time(s),
+            Format::DateTimeRfc1123 => is_date_time_rfc1123(s),
+            Format::Uuid => is_uuid(s),
+            Format::Duration => is_duration(s),
+            Format::ArmId => is_arm_id(s),
+            Format::Uri => uri_scheme_rest(s).is_some(),
+            Format::Url => uri_scheme_rest(s).and_then(|rest| rest.strip_prefix("//")).is_some_and(|rest| {
+                let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+                !authority.is_empty()
+            }),
+            Format::Decimal => is_decimal(s),
+            _ => true,
+        }
+    }
+}
+
+impl From<&str> for Format {
+    fn from(format: &str) -> Self {
+        Format::KNOWN
+            .iter()
+            .find(|known| known.as_str() == format)
+            .cloned()
+            .unwrap_or_else(|| Format::Custom(format.to_owned()))
+    }
+}
+
+impl From<String> for Format {
+    fn from(format: String) -> Self {
+        match Format::from(format.as_str()) {
+            Format::Custom(_) => Format::Custom(format),
+            known => known,
+        }
+    }
+}
+
+impl From<Format> for String {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Custom(format) => format,
+            known => known.as_str().to_owned(),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Format::from(s))
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SchemaCommon {
+    /// The typed `format`.
+    pub fn parsed_format(&self) -> Option<Format> {
+        self.format.as_deref().map(Format::from)
+    }
+}
+
+/// a value that does not match its `format`
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatError {
+    pub format: Format,
+    pub value: Value,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a valid {}", self.value, self.format)
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+type FormatCheck = Arc<dyn Fn(&Value) -> bool + Send + Sync>;
+
+/// The known formats, along with any registered by name.
+/// A registered format takes precedence over a known one of the same name.
+#[derive(Clone, Default)]
+pub struct FormatRegistry {
+    custom: IndexMap<String, FormatCheck>,
+}
+
+impl FormatRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a check for the values of a format.
+    pub fn register(&mut self, format: impl Into<String>, check: impl Fn(&Value) -> bool + Send + Sync + 'static) -> &mut Self {
+        self.custom.insert(format.into(), Arc::new(check));
+        self
+    }
+
+    /// Whether the format is known or registered.
+    pub fn contains(&self, format: &str) -> bool {
+        self.custom.contains_key(format) || !matches!(Format::from(format), Format::Custom(_))
+    }
+
+    /// Checks a value against a format. Values of unknown formats are accepted, as the specification allows any format.
+    pub fn validate(&self, format: &str, value: &Value) -> Result<(), FormatError> {
+        let valid = match self.custom.get(format) {
+            Some(check) => check(value),
+            None => Format::from(format).is_valid(value),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(FormatError {
+                format: Format::from(format),
+                value: value.clone(),
+            })
+        }
+    }
+}
+
+impl fmt::Debug for FormatRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FormatRegistry")
+            .field("custom", &self.custom.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn is_base64(s: &str, is_extra: impl Fn(u8) -> bool, padded: bool) -> bool {
+    let data = s.trim_end_matches('=');
+    let padding = s.len() - data.len();
+    let chars_ok = data.bytes().all(|c| c.is_ascii_alphanumeric() || is_extra(c));
+    let length_ok = if padding > 0 || padded {
+        padding <= 2 && s.len() % 4 == 0
+    } else {
+        data.len() % 4 != 1
+    };
+    chars_ok && length_ok
+}
+
+fn digits(s: &str, len: usize) -> Option<u32> {
+    if s.len() == len && s.bytes().all(|c| c.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn is_ymd(year: u32, month: u32, day: u32) -> bool {
+    (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month)
+}
+
+fn is_date(s: &str) -> bool {
+    let mut parts = s.split('-');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(year), Some(month), Some(day), None) => match (digits(year, 4), digits(month, 2), digits(day, 2)) {
+            (Some(year), Some(month), Some(day)) => is_ymd(year, month, day),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// `HH:MM:SS` with an optional fraction of a second
+fn is_time(s: &str) -> bool {
+    let (time, fraction) = match s.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (s, None),
+    };
+    let mut parts = time.split(':');
+    let valid = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(hour), Some(minute), Some(second), None) => match (digits(hour, 2), digits(minute, 2), digits(second, 2)) {
+            // 60 allows for leap seconds
+            (Some(hour), Some(minute), Some(second)) => hour < 24 && minute < 60 && second <= 60,
+            _ => false,
+        },
+        _ => false,
+    };
+    valid
+        && fraction.map_or(true, |fraction| {
+            !fraction.is_empty() && fraction.bytes().all(|c| c.is_ascii_digit())
+        })
+}
+
+fn is_date_time(s: &str) -> bool {
+    let (date, time) = match s.split_once(['T', 't']) {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let time = match time.strip_suffix(['Z', 'z']) {
+        Some(time) => time,
+        None => match time.len().checked_sub(6).and_then(|i| time.get(i..).map(|offset| (i, offset))) {
+            Some((i, offset)) if offset.starts_with(['+', '-']) && is_offset(&offset[1..]) => &time[..i],
+            _ => return false,
+        },
+    };
+    is_date(date) && is_time(time)
+}
+
+fn is_offset(s: &str) -> bool {
+    match s.split_once(':') {
+        Some((hour, minute)) => matches!((digits(hour, 2), digits(minute, 2)), (Some(hour), Some(minute)) if hour < 24 && minute < 60),
+        None => false,
+    }
+}
+
+const DAY_NAMES: &[&str] = &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTH_NAMES: &[&str] = &["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+fn is_date_time_rfc1123(s: &str) -> bool {
+    let parts: Vec<&str> = s.split(' ').collect();
+    match parts.as_slice() {
+        [day_name, day, month, year, time, "GMT"] => {
+            let month = MONTH_NAMES.iter().position(|name| name == month);
+            match (day_name.strip_suffix(','), digits(day, 2), month, digits(year, 4)) {
+                (Some(day_name), Some(day), Some(month), Some(year)) => {
+                    DAY_NAMES.contains(&day_name) && is_ymd(year, month as u32 + 1, day) && !time.contains('.') && is_time(time)
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.bytes().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == b'-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// such as `P1Y2M3DT4H5M6.5S` or `P2W`
+fn is_duration(s: &str) -> bool {
+    let s = match s.strip_prefix('P') {
+        Some(s) => s,
+        None => return false,
+    };
+    let (date, time) = match s.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (s, None),
+    };
+    let date_ok = date.is_empty() || is_duration_parts(date, "YMWD");
+    let time_ok = time.map_or(true, |time| !time.is_empty() && is_duration_parts(time, "HMS"));
+    (!date.is_empty() || time.is_some()) && date_ok && time_ok
+}
+
+/// Numbers followed by designators, in the order given.
+fn is_duration_parts(s: &str, designators: &str) -> bool {
+    let mut remaining = designators;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() || c == '.' || c == ',' {
+            number.push(c);
+        } else {
+            match remaining.find(c) {
+                Some(i) if !number.is_empty() && number.replace(',', ".").parse::<f64>().is_ok() => {
+                    remaining = &remaining[i + 1..];
+                    number.clear();
+                }
+                _ => return false,
+            }
+        }
+    }
+    number.is_empty()
+}
+
+/// such as `/subscriptions/{id}/resourceGroups/{name}/providers/Microsoft.Compute/virtualMachines/{name}`
+fn is_arm_id(s: &str) -> bool {
+    match s.strip_prefix('/') {
+        Some(path) => {
+            let segments: Vec<&str> = path.strip_suffix('/').unwrap_or(path).split('/').collect();
+            segments.len() >= 2
+                && segments
+                    .iter()
+                    .all(|segment| !segment.is_empty() && !segment.contains(char::is_whitespace))
+        }
+        None => false,
+    }
+}
+
+/// such as `-12.5` or `1e-3`, without the `NaN` and `inf` that `f64` parsing also accepts
+fn is_decimal(s: &str) -> bool {
+    fn is_digits(s: &str) -> bool {
+        !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit())
+    }
+    let s = s.strip_prefix(['-', '+']).unwrap_or(s);
+    let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (s, None),
+    };
+    let mantissa_ok = match mantissa.split_once('.') {
+        Some((whole, fraction)) => {
+            (is_digits(whole) || whole.is_empty()) && (is_digits(fraction) || fraction.is_empty()) && whole.len() + fraction.len() > 0
+        }
+        None => is_digits(mantissa),
+    };
+    let exponent_ok = exponent.map_or(true, |exponent| is_digits(exponent.strip_prefix(['-', '+']).unwrap_or(exponent)));
+    mantissa_ok && exponent_ok
+}
+
+/// The part after the scheme of an absolute URI.
+fn uri_scheme_rest(s: &str) -> Option<&str> {
+    let (scheme, rest) = s.split_once(':')?;
+    let mut chars = scheme.chars();
+    let scheme_ok = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+    if scheme_ok && !s.contains(char::is_whitespace) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validates_values() {
+        let registry = FormatRegistry::new();
+        let valid = [
+            ("int32", json!(-2147483648)),
+            ("int64", json!(9007199254740993i64)),
+            ("float", json!(1.5)),
+            ("byte", json!("aGVsbG8=")),
+            ("base64url", json!("aGVsbG8_-w")),
+            ("date", json!("2024-02-29")),
+            ("date-time", json!("2024-02-29T23:59:60.123+05:30")),
+            ("date-time", json!("2020-01-01T00:00:00Z")),
+            ("date-time-rfc1123", json!("Sun, 06 Nov 1994 08:49:37 GMT")),
+            ("unixtime", json!(1700000000)),
+            ("uuid", json!("123e4567-e89b-12d3-a456-426614174000")),
+            ("duration", json!("P1Y2M3DT4H5M6.5S")),
+            ("duration", json!("PT5M")),
+            ("arm-id", json!("/subscriptions/sub/resourceGroups/rg")),
+            ("uri", json!("urn:isbn:0451450523")),
+            ("url", json!("https://example.com/path?q=1")),
+            ("decimal", json!("3.14")),
+            ("decimal", json!("-1.5e-3")),
+            ("decimal", json!("10")),
+            ("anything", json!(null)),
+        ];
+        for (format, value) in valid.iter() {
+            assert_eq!(registry.validate(format, value), Ok(()), "{}", format);
+        }
+        let invalid = [
+            ("int32", json!(2147483648i64)),
+            ("int64", json!(1.5)),
+            ("float", json!(1e300)),
+            ("byte", json!("aGVsbG8")),
+            ("base64url", json!("aGVs+G8")),
+            ("date", json!("2023-02-29")),
+            ("date-time", json!("2020-01-01T00:00:00")),
+            ("date-time-rfc1123", json!("Sun, 6 Nov 1994 08:49:37 GMT")),
+            ("uuid", json!("123e4567e89b12d3a456426614174000")),
+            ("duration", json!("PT")),
+            ("duration", json!("P1H")),
+            ("arm-id", json!("subscriptions/sub")),
+            ("uri", json!("/relative")),
+            ("url", json!("mailto:someone@example.com")),
+            ("date", json!(20200101)),
+            ("decimal", json!("NaN")),
+            ("decimal", json!("inf")),
+            ("decimal", json!("infinity")),
+            ("decimal", json!("-Infinity")),
+            ("decimal", json!("1e")),
+            ("decimal", json!(".")),
+        ];
+        for (format, value) in invalid.iter() {
+            assert!(registry.validate(format, value).is_err(), "{} {}", format, value);
+        }
+    }
+
+    #[test]
+    fn registers_custom_formats() {
+        let mut registry = FormatRegistry::new();
+        assert!(!registry.contains("semver"));
+        registry.register("semver", |value| value.as_str().is_some_and(|s| s.split('.').count() == 3));
+        assert!(registry.contains("semver"));
+        assert_eq!(registry.validate("semver", &json!("1.2.3")), Ok(()));
+        let err = registry.validate("semver", &json!("1.2")).unwrap_err();
+        assert_eq!(err.format, Format::Custom("semver".to_owned()));
+        assert_eq!(err.to_string(), "\"1.2\" is not a valid semver");
+
+        let common: SchemaCommon = serde_json::from_value(json!({ "type": "string", "format": "date-time-rfc1123" })).unwrap();
+        assert_eq!(common.parsed_format(), Some(Format::DateTimeRfc1123));
+        assert_eq!(serde_json::to_value(Format::ArmId).unwrap(), json!("arm-id"));
+    }
+}
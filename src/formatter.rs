@@ -0,0 +1,609 @@
+//! Formats documents in a canonical layout
+//! Fixed fields are ordered as they are listed in the Swagger 2.0 specification, followed by any other fields in their original order.
+//! Formatting works on a JSON value instead of the `OpenAPI` type, so unknown content is kept.
+//! The value is an `OrderedValue`, which keeps the order of keys without the `preserve_order` feature of `serde_json`.
+//! https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md
+
+use crate::{Error, Result};
+use indexmap::IndexMap;
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Number;
+use std::{fmt, ops::Range, path::Path};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatOptions {
+    /// The number of spaces to indent JSON with.
+    pub indent: usize,
+    /// Sort `definitions` by name.
+    pub sort_definitions: bool,
+    /// Sort `paths` and `x-ms-paths` by path.
+    pub sort_paths: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: 2,
+            sort_definitions: false,
+            sort_paths: false,
+        }
+    }
+}
+
+/// A JSON value whose objects keep their keys in order.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum OrderedValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<OrderedValue>),
+    Object(IndexMap<String, OrderedValue>),
+}
+
+impl<'de> Deserialize<'de> for OrderedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OrderedValueVisitor)
+    }
+}
+
+struct OrderedValueVisitor;
+
+impl<'de> Visitor<'de> for OrderedValueVisitor {
+    type Value = OrderedValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<OrderedValue, D::Error> {
+        OrderedValue::deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<OrderedValue, E> {
+        // like `serde_json::Value`, a number that is not finite becomes `null`
+        Ok(Number::from_f64(value).map_or(OrderedValue::Null, OrderedValue::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::String(value.to_owned()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<OrderedValue, E> {
+        Ok(OrderedValue::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<OrderedValue, A::Error> {
+        let mut array = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            array.push(item);
+        }
+        Ok(OrderedValue::Array(array))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderedValue, A::Error> {
+        let mut object = IndexMap::new();
+        while let Some((key, value)) = map.next_entry()? {
+            object.insert(key, value);
+        }
+        Ok(OrderedValue::Object(object))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Root,
+    Info,
+    Contact,
+    License,
+    PathItem,
+    Operation,
+    Parameter,
+    Items,
+    Schema,
+    Response,
+    Header,
+    Tag,
+    SecurityScheme,
+    ExternalDocs,
+}
+
+const ROOT: &[&str] = &[
+    "swagger",
+    "info",
+    "host",
+    "basePath",
+    "schemes",
+    "consumes",
+    "produces",
+    "paths",
+    "x-ms-paths",
+    "definitions",
+    "parameters",
+    "responses",
+    "securityDefinitions",
+    "security",
+    "tags",
+    "externalDocs",
+];
+const INFO: &[&str] = &["title", "description", "termsOfService", "contact", "license", "version"];
+const CONTACT: &[&str] = &["name", "url", "email"];
+const LICENSE: &[&str] = &["name", "url"];
+const PATH_ITEM: &[&str] = &["$ref", "get", "put", "post", "delete", "options", "head", "patch", "parameters"];
+const OPERATION: &[&str] = &[
+    "tags",
+    "summary",
+    "description",
+    "externalDocs",
+    "operationId",
+    "consumes",
+    "produces",
+    "parameters",
+    "responses",
+    "schemes",
+    "deprecated",
+    "security",
+];
+const VALIDATIONS: &[&str] = &[
+    "default",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "enum",
+    "multipleOf",
+];
+const PARAMETER: &[&str] = &[
+    "$ref",
+    "name",
+    "in",
+    "description",
+    "required",
+    "schema",
+    "type",
+    "format",
+    "allowEmptyValue",
+    "items",
+    "collectionFormat",
+];
+const ITEMS: &[&str] = &["type", "format", "items", "collectionFormat"];
+const SCHEMA: &[&str] = &[
+    "$ref",
+    "format",
+    "title",
+    "description",
+    "default",
+    "multipleOf",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "maxProperties",
+    "minProperties",
+    "required",
+    "enum",
+    "type",
+    "items",
+    "allOf",
+    "properties",
+    "additionalProperties",
+    "discriminator",
+    "readOnly",
+    "xml",
+    "externalDocs",
+    "example",
+];
+const RESPONSE: &[&str] = &["$ref", "description", "schema", "headers", "examples"];
+const HEADER: &[&str] = &["description", "type", "format", "items", "collectionFormat"];
+const TAG: &[&str] = &["name", "description", "externalDocs"];
+const SECURITY_SCHEME: &[&str] = &[
+    "type",
+    "description",
+    "name",
+    "in",
+    "flow",
+    "authorizationUrl",
+    "tokenUrl",
+    "scopes",
+];
+const EXTERNAL_DOCS: &[&str] = &["description", "url"];
+
+/// Reorders the fields of a document into the canonical layout.
+pub fn canonicalize(spec: &mut OrderedValue, options: &FormatOptions) {
+    canonicalize_kind(spec, Kind::Root, options);
+}
+
+fn canonicalize_kind(value: &mut OrderedValue, kind: Kind, options: &FormatOptions) {
+    let object = match value {
+        OrderedValue::Object(object) => object,
+        _ => return,
+    };
+    let order: &[&[&str]] = match kind {
+        Kind::Root => &[ROOT],
+        Kind::Info => &[INFO],
+        Kind::Contact => &[CONTACT],
+        Kind::License => &[LICENSE],
+        Kind::PathItem => &[PATH_ITEM],
+        Kind::Operation => &[OPERATION],
+        Kind::Parameter => &[PARAMETER, VALIDATIONS],
+        Kind::Items => &[ITEMS, VALIDATIONS],
+        Kind::Schema => &[SCHEMA],
+        Kind::Response => &[RESPONSE],
+        Kind::Header => &[HEADER, VALIDATIONS],
+        Kind::Tag => &[TAG],
+        Kind::SecurityScheme => &[SECURITY_SCHEME],
+        Kind::ExternalDocs => &[EXTERNAL_DOCS],
+    };
+    reorder(object, order.iter().flat_map(|keys| keys.iter()));
+
+    for (key, child) in object.iter_mut() {
+        match (kind, key.as_str()) {
+            (Kind::Root, "info") => canonicalize_kind(child, Kind::Info, options),
+            (Kind::Root, "paths") | (Kind::Root, "x-ms-paths") => {
+                canonicalize_map(child, Kind::PathItem, options);
+                if options.sort_paths {
+                    sort_keys(child);
+                }
+            }
+            (Kind::Root, "definitions") => {
+                canonicalize_map(child, Kind::Schema, options);
+                if options.sort_definitions {
+                    sort_keys(child);
+                }
+            }
+            (Kind::Root, "parameters") => canonicalize_map(child, Kind::Parameter, options),
+            (Kind::Root, "responses") | (Kind::Operation, "responses") => canonicalize_map(child, Kind::Response, options),
+            (Kind::Root, "securityDefinitions") => canonicalize_map(child, Kind::SecurityScheme, options),
+            (Kind::Root, "tags") => canonicalize_array(child, Kind::Tag, options),
+            (Kind::Info, "contact") => canonicalize_kind(child, Kind::Contact, options),
+            (Kind::Info, "license") => canonicalize_kind(child, Kind::License, options),
+            (Kind::PathItem, "get")
+            | (Kind::PathItem, "put")
+            | (Kind::PathItem, "post")
+            | (Kind::PathItem, "delete")
+            | (Kind::PathItem, "options")
+            | (Kind::PathItem, "head")
+            | (Kind::PathItem, "patch") => canonicalize_kind(child, Kind::Operation, options),
+            (Kind::PathItem, "parameters") | (Kind::Operation, "parameters") => canonicalize_array(child, Kind::Parameter, options),
+            (Kind::Parameter, "schema") | (Kind::Response, "schema") => canonicalize_kind(child, Kind::Schema, options),
+            (Kind::Parameter, "items") | (Kind::Items, "items") | (Kind::Header, "items") => canonicalize_kind(child, Kind::Items, options),
+            (Kind::Schema, "items") if matches!(child, OrderedValue::Array(_)) => canonicalize_array(child, Kind::Schema, options),
+            (Kind::Schema, "items") | (Kind::Schema, "additionalProperties") => canonicalize_kind(child, Kind::Schema, options),
+            (Kind::Schema, "allOf") => canonicalize_array(child, Kind::Schema, options),
+            (Kind::Schema, "properties") => canonicalize_map(child, Kind::Schema, options),
+            (Kind::Response, "headers") => canonicalize_map(child, Kind::Header, options),
+            (_, "externalDocs") => canonicalize_kind(child, Kind::ExternalDocs, options),
+            _ => {}
+        }
+    }
+}
+
+fn canonicalize_map(value: &mut OrderedValue, kind: Kind, options: &FormatOptions) {
+    if let OrderedValue::Object(object) = value {
+        for child in object.values_mut() {
+            canonicalize_kind(child, kind, options);
+        }
+    }
+}
+
+fn canonicalize_array(value: &mut OrderedValue, kind: Kind, options: &FormatOptions) {
+    if let OrderedValue::Array(array) = value {
+        for child in array {
+            canonicalize_kind(child, kind, options);
+        }
+    }
+}
+
+/// Moves the given keys to the front, in order. Other keys keep their relative order.
+fn reorder<'a>(object: &mut IndexMap<String, OrderedValue>, keys: impl Iterator<Item = &'a &'a str>) {
+    let mut rest = std::mem::take(object);
+    for key in keys {
+        if let Some(value) = rest.shift_remove(*key) {
+            object.insert((*key).to_owned(), value);
+        }
+    }
+    object.extend(rest);
+}
+
+fn sort_keys(value: &mut OrderedValue) {
+    if let OrderedValue::Object(object) = value {
+        object.sort_keys();
+    }
+}
+
+/// Formats a JSON document.
+pub fn format_json(input: &[u8], options: &FormatOptions) -> Result<String, serde_json::Error> {
+    let mut spec: OrderedValue = serde_json::from_slice(input)?;
+    canonicalize(&mut spec, options);
+    let indent = " ".repeat(options.indent);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut bytes = Vec::new();
+    spec.serialize(&mut serde_json::Serializer::with_formatter(&mut bytes, formatter))?;
+    bytes.push(b'\n');
+    Ok(String::from_utf8(bytes).expect("serde_json writes valid UTF-8"))
+}
+
+/// Formats a YAML document. YAML is always indented with two spaces.
+#[cfg(feature = "yaml")]
+pub fn format_yaml(input: &[u8], options: &FormatOptions) -> Result<String, serde_yaml::Error> {
+    let mut spec: OrderedValue = serde_yaml::from_slice(input)?;
+    canonicalize(&mut spec, options);
+    serde_yaml::to_string(&spec)
+}
+
+/// Formats a document, using the path to pick the format and to report errors.
+pub fn format_spec(path: impl AsRef<Path>, input: &[u8], options: &FormatOptions) -> Result<String> {
+    let path = path.as_ref();
+    #[cfg(feature = "yaml")]
+    {
+        if crate::is_yaml(path) {
+            return format_yaml(input, options).map_err(|source| Error::Yaml {
+                path: path.to_owned(),
+                source,
+            });
+        }
+    }
+    format_json(input, options).map_err(|source| Error::Json {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Returns true if formatting the document would not change it.
+pub fn is_formatted(path: impl AsRef<Path>, input: &[u8], options: &FormatOptions) -> Result<bool> {
+    Ok(format_spec(path, input, options)?.as_bytes() == input)
+}
+
+/// Removes members of the objects at the root of a JSON document, such as `definitions`, leaving every other byte as it was.
+/// `remove` is given the name of the root member and the name of the member within it.
+pub fn remove_json_members(input: &str, remove: impl Fn(&str, &str) -> bool) -> Result<String, serde_json::Error> {
+    // the scanning below relies on the document being valid
+    serde_json::from_str::<serde::de::IgnoredAny>(input)?;
+    let bytes = input.as_bytes();
+    let mut removed = Vec::new();
+    let root = skip_whitespace(bytes, 0);
+    if bytes[root] == b'{' {
+        for section in json_members(bytes, root)? {
+            if bytes[section.value.start] == b'{' {
+                let members = json_members(bytes, section.value.start)?;
+                let keep: Vec<bool> = members.iter().map(|member| !remove(&section.name, &member.name)).collect();
+                removed.extend(removed_ranges(bytes, section.value.start, &members, &keep));
+            }
+        }
+    }
+    let mut output = input.to_owned();
+    for range in removed.into_iter().rev() {
+        output.replace_range(range, "");
+    }
+    Ok(output)
+}
+
+struct JsonMember {
+    name: String,
+    /// where the key starts
+    start: usize,
+    value: Range<usize>,
+}
+
+/// The members of the object starting at `open`.
+fn json_members(bytes: &[u8], open: usize) -> Result<Vec<JsonMember>, serde_json::Error> {
+    let mut members = Vec::new();
+    let mut i = skip_whitespace(bytes, open + 1);
+    while bytes[i] != b'}' {
+        let key_end = skip_json_value(bytes, i);
+        let name = serde_json::from_slice(&bytes[i..key_end])?;
+        let value_start = skip_whitespace(bytes, skip_whitespace(bytes, key_end) + 1);
+        let value_end = skip_json_value(bytes, value_start);
+        members.push(JsonMember {
+            name,
+            start: i,
+            value: value_start..value_end,
+        });
+        i = skip_whitespace(bytes, value_end);
+        if bytes[i] == b',' {
+            i = skip_whitespace(bytes, i + 1);
+        }
+    }
+    Ok(members)
+}
+
+/// The byte ranges to remove so that only the kept members remain, along with the commas and whitespace between them.
+fn removed_ranges(bytes: &[u8], open: usize, members: &[JsonMember], keep: &[bool]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    match keep.iter().position(|keep| *keep) {
+        Some(first) => {
+            // those before the first kept member are removed up to its key, the others from the end of the kept member before them
+            if first > 0 {
+                ranges.push(members[0].start..members[first].start);
+            }
+            let mut last_kept = first;
+            for index in first + 1..members.len() {
+                if keep[index] {
+                    last_kept = index;
+                } else if keep.get(index + 1) != Some(&false) {
+                    ranges.push(members[last_kept].value.end..members[index].value.end);
+                }
+            }
+        }
+        None => {
+            if let Some(last) = members.last() {
+                ranges.push(open + 1..skip_whitespace(bytes, last.value.end));
+            }
+        }
+    }
+    ranges
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize) -> usize {
+    while bytes.get(i).is_some_and(|c| c.is_ascii_whitespace()) {
+        i += 1;
+    }
+    i
+}
+
+/// The end of the value starting at `i`.
+fn skip_json_value(bytes: &[u8], mut i: usize) -> usize {
+    let mut depth = 0;
+    loop {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'{' | b'[' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' | b']' => {
+                depth -= 1;
+                i += 1;
+            }
+            _ if depth == 0 => {
+                while bytes
+                    .get(i)
+                    .is_some_and(|c| !matches!(c, b',' | b'}' | b']') && !c.is_ascii_whitespace())
+                {
+                    i += 1;
+                }
+                return i;
+            }
+            _ => i += 1,
+        }
+        if depth == 0 {
+            return i;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_fields_and_keeps_unknown_content() {
+        let input = br#"{"paths":{"/b":{"get":{"responses":{"200":{"description":"ok"}},"operationId":"B"}},"/a":{}},"x-unknown":[1],"swagger":"2.0","info":{"version":"1","title":"t"},"definitions":{"B":{"type":"object","description":"b"},"A":{}}}"#;
+        let options = FormatOptions {
+            indent: 1,
+            sort_definitions: true,
+            sort_paths: false,
+        };
+        let output = format_json(input, &options).unwrap();
+        assert_eq!(
+            output,
+            r#"{
+ "swagger": "2.0",
+ "info": {
+  "title": "t",
+  "version": "1"
+ },
+ "paths": {
+  "/b": {
+   "get": {
+    "operationId": "B",
+    "responses": {
+     "200": {
+      "description": "ok"
+     }
+    }
+   }
+  },
+  "/a": {}
+ },
+ "definitions": {
+  "A": {},
+  "B": {
+   "description": "b",
+   "type": "object"
+  }
+ },
+ "x-unknown": [
+  1
+ ]
+}
+"#
+        );
+        assert!(is_formatted("spec.json", output.as_bytes(), &options).unwrap());
+        assert!(!is_formatted("spec.json", input, &options).unwrap());
+    }
+
+    #[test]
+    fn removes_members_and_keeps_the_rest_of_the_text() {
+        let input = r#"{
+    "definitions": {
+        "A": {},
+        "B": { "x": [1, 2] },
+        "C": {}, "D": {}
+    },
+    "parameters": { "P\"": {} },
+    "x-ms": { "A": {} }
+}
+"#;
+        let removed = |names: &'static [&'static str]| {
+            remove_json_members(input, |section, name| section != "x-ms" && names.contains(&name)).unwrap()
+        };
+        assert_eq!(
+            removed(&["A", "C"]),
+            r#"{
+    "definitions": {
+        "B": { "x": [1, 2] }, "D": {}
+    },
+    "parameters": { "P\"": {} },
+    "x-ms": { "A": {} }
+}
+"#
+        );
+        assert_eq!(
+            removed(&["C", "D", "P\""]),
+            r#"{
+    "definitions": {
+        "A": {},
+        "B": { "x": [1, 2] }
+    },
+    "parameters": {},
+    "x-ms": { "A": {} }
+}
+"#
+        );
+        assert_eq!(removed(&[]), input);
+        assert!(remove_json_members("{", |_, _| true).is_err());
+    }
+}
@@ -1,12 +1,61 @@
-use serde::{Deserialize, Serialize};
+use crate::{next_value, CollectionFormat, FieldsVisitor, ReadFields, SchemaCommon};
+use serde::{de::MapAccess, Deserialize, Deserializer, Serialize};
 
 /// see Response Headers https://swagger.io/docs/specification/2-0/describing-responses/
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#header-object
+#[derive(Clone, Debug, Serialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct Header {
-    #[serde(rename = "type")]
-    pub type_: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub format: Option<String>,
+    /// the `type`, `format`, `items`, `default`, validations and `x-ms-client-name`
+    #[serde(flatten)]
+    pub common: SchemaCommon,
+
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
+    pub collection_format: Option<CollectionFormat>,
+
+    /// collects all of the headers that start with the prefix into a dictionary
+    /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-header-collection-prefix
+    #[serde(rename = "x-ms-header-collection-prefix", skip_serializing_if = "Option::is_none")]
+    pub x_ms_header_collection_prefix: Option<String>,
+}
+
+impl<'de> ReadFields<'de> for Header {
+    fn read_field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "collectionFormat" => self.collection_format = next_value(map, key)?,
+            "x-ms-header-collection-prefix" => self.x_ms_header_collection_prefix = next_value(map, key)?,
+            _ => return self.common.read_field(key, map),
+        }
+        Ok(true)
+    }
+}
+
+impl<'de> Deserialize<'de> for Header {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FieldsVisitor::new("a header"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataType;
+    use serde_json::json;
+
+    #[test]
+    fn roundtrips_headers() {
+        let value = json!({
+            "type": "array",
+            "description": "The metadata of the blob.",
+            "items": { "type": "integer", "format": "int32", "minimum": 0, "maximum": 10 },
+            "collectionFormat": "csv",
+            "x-ms-client-name": "Metadata",
+            "x-ms-header-collection-prefix": "x-ms-meta-"
+        });
+        let header: Header = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(header.common.type_, Some(DataType::Array));
+        assert_eq!(header.collection_format, Some(CollectionFormat::Csv));
+        assert_eq!(header.x_ms_header_collection_prefix.as_deref(), Some("x-ms-meta-"));
+        assert_eq!(serde_json::to_value(&header).unwrap(), value);
+    }
 }
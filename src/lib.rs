@@ -1,21 +1,63 @@
 mod autorest;
+mod borrowed;
+mod builder;
+mod bundle;
+mod cache;
+mod catalog;
 mod contact;
+mod diff;
+mod enums;
+pub mod error;
 pub mod example;
 mod external_documentation;
+mod fields;
+mod format;
+mod formatter;
 mod header;
 mod info;
 mod license;
+mod load;
+mod merge;
+mod naming;
 mod openapi;
+mod openapi_schema;
 mod operation;
+mod operation_group;
+mod pageable;
 mod parameter;
 mod paths;
+mod reachability;
+#[cfg(feature = "yaml")]
+mod readme;
 mod reference;
+mod reference_graph;
 mod schema;
 mod security;
 mod status_code;
+mod subset;
 mod tag;
+mod types;
+mod validate;
+mod walk;
+mod xml;
+#[cfg(feature = "xml")]
+mod xml_value;
 
+pub use self::error::Error;
 pub use self::{
-    autorest::*, contact::*, external_documentation::*, header::*, info::*, license::*, openapi::*, operation::*, parameter::*, paths::*,
-    reference::*, schema::*, security::*, status_code::*, tag::*,
+    autorest::*, borrowed::*, bundle::*, cache::*, catalog::*, contact::*, diff::*, enums::*, external_documentation::*, format::*,
+    formatter::*, header::*, info::*, license::*, load::*, merge::*, naming::*, openapi::*, openapi_schema::*, operation::*,
+    operation_group::*, pageable::*, parameter::*, paths::*, reachability::*, reference::*, reference_graph::*, schema::*, security::*,
+    status_code::*, subset::*, tag::*, types::*, validate::*, walk::*, xml::*,
 };
+// public as `error::Result`, so that `use autorust_openapi::*` does not shadow `std::result::Result`
+pub(crate) use self::error::Result;
+pub(crate) use self::fields::*;
+
+#[cfg(feature = "yaml")]
+pub use self::readme::*;
+#[cfg(feature = "xml")]
+pub use self::xml_value::*;
+
+#[cfg(feature = "derive")]
+pub use autorust_openapi_derive::OpenApiSchema;
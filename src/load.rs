@@ -0,0 +1,38 @@
+use crate::{Error, OpenAPI, Result};
+use std::{fs, path::Path};
+
+/// Reads an OpenAPI document from a file.
+/// Files ending in `.yaml` or `.yml` are read as YAML when the `yaml` feature is enabled, all others as JSON.
+pub fn read_spec(path: impl AsRef<Path>) -> Result<OpenAPI> {
+    let path = path.as_ref();
+    // reading the whole file upfront is much faster than using a BufReader
+    // https://github.com/serde-rs/json/issues/160
+    let bytes = fs::read(path).map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })?;
+    parse_spec(path, &bytes)
+}
+
+/// Parses an OpenAPI document, using the path to pick the format and to report errors.
+pub fn parse_spec(path: impl AsRef<Path>, bytes: &[u8]) -> Result<OpenAPI> {
+    let path = path.as_ref();
+    #[cfg(feature = "yaml")]
+    {
+        if is_yaml(path) {
+            return serde_yaml::from_slice(bytes).map_err(|source| Error::Yaml {
+                path: path.to_owned(),
+                source,
+            });
+        }
+    }
+    serde_json::from_slice(bytes).map_err(|source| Error::Json {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+/// Returns true if the file extension is `.yaml` or `.yml`.
+pub fn is_yaml(path: impl AsRef<Path>) -> bool {
+    matches!(path.as_ref().extension().and_then(|ext| ext.to_str()), Some("yaml") | Some("yml"))
+}
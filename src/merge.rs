@@ -0,0 +1,296 @@
+//! Merges several documents into one, as AutoRest does with the input files of a tag
+
+use crate::*;
+use indexmap::IndexMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    path::{Component as PathComponent, Path, PathBuf},
+};
+
+/// The result of merging documents.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Merged {
+    pub spec: OpenAPI,
+    /// Items defined differently by more than one document. The first definition is kept.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// An item, located by a JSON Pointer, that a document defines differently than an earlier one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergeConflict {
+    pub pointer: String,
+    /// the document whose definition is kept
+    pub kept: PathBuf,
+    /// the document whose definition is dropped
+    pub dropped: PathBuf,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in {} conflicts with {}",
+            self.pointer,
+            self.dropped.display(),
+            self.kept.display()
+        )
+    }
+}
+
+/// Reads and merges documents.
+pub fn merge_files<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Result<Merged> {
+    let specs = paths
+        .into_iter()
+        .map(|path| {
+            let path = path.as_ref();
+            Ok((path.to_owned(), read_spec(path)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(merge(specs))
+}
+
+/// Merges documents along with the paths they were read from.
+/// The paths, definitions, parameters, responses, security definitions and tags are combined,
+/// while the other fields come from the first document.
+/// References to another one of the documents become local references.
+/// Other references to files are kept, relative to the directory of the first document.
+pub fn merge(specs: impl IntoIterator<Item = (PathBuf, OpenAPI)>) -> Merged {
+    let mut specs: Vec<(PathBuf, OpenAPI)> = specs.into_iter().map(|(path, spec)| (normalize(&path), spec)).collect();
+    let dir = match specs.first() {
+        Some((path, _)) => parent(path),
+        None => return Merged::default(),
+    };
+    let paths: Vec<PathBuf> = specs.iter().map(|(path, _)| path.clone()).collect();
+    for (path, spec) in &mut specs {
+        let spec_dir = parent(path);
+        for reference in spec.references_mut() {
+            if let Some(file) = &reference.file {
+                let target = normalize(&spec_dir.join(file));
+                if paths.contains(&target) {
+                    reference.file = None;
+                } else if spec_dir != dir {
+                    reference.file = Some(relative(&dir, &target));
+                }
+            }
+        }
+    }
+
+    let mut specs = specs.into_iter();
+    let (first_path, mut merged) = specs.next().expect("not empty");
+    let mut merger = Merger {
+        origins: HashMap::new(),
+        conflicts: Vec::new(),
+    };
+    merger.claim_all("/paths", &merged.paths, &first_path);
+    merger.claim_all("/x-ms-paths", &merged.x_ms_paths, &first_path);
+    merger.claim_all("/definitions", &merged.definitions, &first_path);
+    merger.claim_all("/parameters", &merged.parameters, &first_path);
+    merger.claim_all("/responses", &merged.responses, &first_path);
+    merger.claim_all("/securityDefinitions", &merged.security_definitions, &first_path);
+    let mut tags: IndexMap<String, Tag> = merged.tags.drain(..).map(|tag| (tag.name.clone(), tag)).collect();
+    merger.claim_all("/tags", &tags, &first_path);
+
+    for (path, spec) in specs {
+        merger.merge_map("/paths", &mut merged.paths, spec.paths, &path);
+        merger.merge_map("/x-ms-paths", &mut merged.x_ms_paths, spec.x_ms_paths, &path);
+        merger.merge_map("/definitions", &mut merged.definitions, spec.definitions, &path);
+        merger.merge_map("/parameters", &mut merged.parameters, spec.parameters, &path);
+        merger.merge_map("/responses", &mut merged.responses, spec.responses, &path);
+        merger.merge_map(
+            "/securityDefinitions",
+            &mut merged.security_definitions,
+            spec.security_definitions,
+            &path,
+        );
+        let spec_tags = spec.tags.into_iter().map(|tag| (tag.name.clone(), tag)).collect();
+        merger.merge_map("/tags", &mut tags, spec_tags, &path);
+        for requirement in spec.security {
+            if !merged.security.contains(&requirement) {
+                merged.security.push(requirement);
+            }
+        }
+    }
+    merged.tags = tags.into_iter().map(|(_, tag)| tag).collect();
+    Merged {
+        spec: merged,
+        conflicts: merger.conflicts,
+    }
+}
+
+struct Merger {
+    /// the document each item came from, by pointer
+    origins: HashMap<String, PathBuf>,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl Merger {
+    fn claim_all<K: ToString, V>(&mut self, prefix: &str, map: &IndexMap<K, V>, path: &Path) {
+        for name in map.keys() {
+            self.origins.insert(pointer(prefix, name), path.to_owned());
+        }
+    }
+
+    /// Adds the items that are not in the target yet. Identical duplicates are skipped and others are reported.
+    fn merge_map<K, V>(&mut self, prefix: &str, target: &mut IndexMap<K, V>, source: IndexMap<K, V>, path: &Path)
+    where
+        K: Hash + Eq + ToString,
+        V: PartialEq,
+    {
+        for (name, value) in source {
+            let pointer = pointer(prefix, &name);
+            match target.get(&name) {
+                None => {
+                    self.origins.insert(pointer, path.to_owned());
+                    target.insert(name, value);
+                }
+                Some(existing) if *existing == value => {}
+                Some(_) => self.conflicts.push(MergeConflict {
+                    kept: self.origins[&pointer].clone(),
+                    dropped: path.to_owned(),
+                    pointer,
+                }),
+            }
+        }
+    }
+}
+
+fn pointer(prefix: &str, name: &impl ToString) -> String {
+    format!("{}/{}", prefix, escape_pointer_token(&name.to_string()))
+}
+
+fn parent(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// The path of `target` relative to `dir`, both normalized, with `/` separators as in a `$ref`.
+/// The target is kept as it is when `dir` goes up further than it, since the names of those directories are unknown.
+fn relative(dir: &Path, target: &Path) -> String {
+    let dir: Vec<PathComponent> = dir.components().collect();
+    let target: Vec<PathComponent> = target.components().collect();
+    let common = dir.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    let rest = &dir[common..];
+    if rest.iter().any(|component| !matches!(component, PathComponent::Normal(_))) {
+        return target
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+    }
+    let ups = rest.iter().map(|_| "..".into());
+    let downs = target[common..].iter().map(|component| component.as_os_str().to_string_lossy());
+    ups.chain(downs).collect::<Vec<_>>().join("/")
+}
+
+/// Removes `.` and `..` components without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            PathComponent::CurDir => {}
+            PathComponent::ParentDir if matches!(normalized.components().next_back(), Some(PathComponent::Normal(_))) => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn combines_documents_and_reports_conflicts() {
+        let pools: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": { "title": "Batch" },
+            "paths": {
+                "/pools": { "get": { "responses": { "200": { "schema": { "$ref": "./jobs.json#/definitions/Job" } } } } }
+            },
+            "definitions": {
+                "Error": { "type": "string" },
+                "Resource": { "$ref": "../../common/types.json#/definitions/Resource" }
+            },
+            "tags": [{ "name": "Pools" }]
+        }))
+        .unwrap();
+        let jobs: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": { "title": "Jobs" },
+            "paths": {
+                "/jobs": { "get": { "responses": { "200": { "schema": { "$ref": "#/definitions/Job" } } } } }
+            },
+            "definitions": {
+                "Error": { "type": "string" },
+                "Job": { "type": "object" },
+                "Resource": { "type": "object" }
+            },
+            "tags": [{ "name": "Jobs" }, { "name": "Pools" }]
+        }))
+        .unwrap();
+        let merged = merge(vec![
+            (PathBuf::from("specs/batch/pools.json"), pools),
+            (PathBuf::from("specs/batch/./jobs.json"), jobs),
+        ]);
+        assert_eq!(
+            merged.conflicts,
+            vec![MergeConflict {
+                pointer: "/definitions/Resource".to_owned(),
+                kept: PathBuf::from("specs/batch/pools.json"),
+                dropped: PathBuf::from("specs/batch/jobs.json"),
+            }]
+        );
+        let spec = serde_json::to_value(merged.spec).unwrap();
+        assert_eq!(spec["info"]["title"], "Batch");
+        assert_eq!(
+            spec["paths"]["/pools"]["get"]["responses"]["200"]["schema"]["$ref"],
+            "#/definitions/Job"
+        );
+        assert_eq!(
+            spec["definitions"]["Resource"]["$ref"],
+            "../../common/types.json#/definitions/Resource"
+        );
+        assert_eq!(spec["definitions"].as_object().unwrap().len(), 3);
+        assert_eq!(spec["tags"], json!([{ "name": "Pools" }, { "name": "Jobs" }]));
+    }
+
+    #[test]
+    fn keeps_external_references_relative_to_the_first_document() {
+        let pools: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": { "title": "Batch" },
+            "paths": {},
+            "definitions": { "Pool": { "type": "object" } }
+        }))
+        .unwrap();
+        let jobs: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": { "title": "Jobs" },
+            "paths": {},
+            "definitions": {
+                "Job": {
+                    "properties": {
+                        "pool": { "$ref": "../batch/stable/pools.json#/definitions/Pool" },
+                        "resource": { "$ref": "../../common/types.json#/definitions/Resource" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let merged = merge(vec![
+            (PathBuf::from("specs/batch/stable/pools.json"), pools),
+            (PathBuf::from("specs/jobs/jobs.json"), jobs),
+        ]);
+        let spec = serde_json::to_value(merged.spec).unwrap();
+        let properties = &spec["definitions"]["Job"]["properties"];
+        assert_eq!(properties["pool"]["$ref"], "#/definitions/Pool");
+        assert_eq!(properties["resource"]["$ref"], "../../../common/types.json#/definitions/Resource");
+
+        assert_eq!(relative(Path::new("../a"), Path::new("../../b.json")), "../../b.json");
+        assert_eq!(relative(Path::new("a/b"), Path::new("a/c/d.json")), "../c/d.json");
+    }
+}
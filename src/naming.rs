@@ -0,0 +1,227 @@
+//! Identifiers for generated code, from the names used in a document
+
+use crate::*;
+use indexmap::IndexMap;
+
+/// The strict and reserved keywords of Rust, which can not be used as identifiers.
+pub const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do", "dyn", "else", "enum", "extern",
+    "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv",
+    "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
+
+/// The name used for names without letters or digits.
+const PLACEHOLDER: &str = "value";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// `snake_case`, for functions, modules and fields
+    Snake,
+    /// `PascalCase`, for types and enum variants
+    Pascal,
+    /// `SCREAMING_SNAKE_CASE`, for constants
+    ScreamingSnake,
+}
+
+impl Case {
+    /// Converts the name, splitting words at case changes and at anything that is not a letter or digit.
+    pub fn convert(self, name: &str) -> String {
+        let words = words(name);
+        match self {
+            Case::Snake => words.iter().map(|word| word.to_lowercase()).collect::<Vec<_>>().join("_"),
+            Case::ScreamingSnake => words.iter().map(|word| word.to_uppercase()).collect::<Vec<_>>().join("_"),
+            Case::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+
+    /// Converts the name to a valid Rust identifier.
+    /// Keywords get a trailing underscore, as in `type_`, and a leading digit gets a leading underscore.
+    /// A name without letters or digits becomes the placeholder `value`, in the case, so names such as `-` and `+` collide in a `NameScope`.
+    pub fn ident(self, name: &str) -> String {
+        let mut ident = self.convert(name);
+        if ident.is_empty() {
+            ident = self.convert(PLACEHOLDER);
+        }
+        if ident.starts_with(|c: char| c.is_ascii_digit()) {
+            ident.insert(0, '_');
+        }
+        if RUST_KEYWORDS.contains(&ident.as_str()) {
+            ident.push('_');
+        }
+        ident
+    }
+}
+
+pub fn snake_case_ident(name: &str) -> String {
+    Case::Snake.ident(name)
+}
+
+pub fn pascal_case_ident(name: &str) -> String {
+    Case::Pascal.ident(name)
+}
+
+pub fn screaming_snake_case_ident(name: &str) -> String {
+    Case::ScreamingSnake.ident(name)
+}
+
+/// Splits the name into words. A word ends before an upper case letter that follows a lower case letter or digit,
+/// and before the last upper case letter of an acronym followed by a lower case letter, so `HTTPServer2Id` is `HTTP`, `Server2`, `Id`.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase() || previous.is_ascii_digit() || (previous.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+/// Splits an `operationId` such as `PrivateClouds_List` into its group and method, at the first underscore.
+/// An `operationId` without an underscore has no group.
+pub fn split_operation_id(operation_id: &str) -> (Option<&str>, &str) {
+    match operation_id.split_once('_') {
+        Some((group, method)) if !group.is_empty() && !method.is_empty() => (Some(group), method),
+        _ => (None, operation_id),
+    }
+}
+
+impl SchemaCommon {
+    /// The `x-ms-client-name`, or else the name from the document.
+    pub fn client_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.x_ms_client_name.as_deref().unwrap_or(name)
+    }
+}
+
+impl Parameter {
+    /// The `x-ms-client-name`, or else the parameter name.
+    pub fn client_name(&self) -> &str {
+        match &self.kind {
+            ParameterKind::Body(body) => body.x_ms_client_name.as_deref().unwrap_or(&self.name),
+            ParameterKind::Path(parameter)
+            | ParameterKind::Query(parameter)
+            | ParameterKind::Header(parameter)
+            | ParameterKind::FormData(parameter) => parameter.common.client_name(&self.name),
+        }
+    }
+}
+
+impl<'a> EnumValue<'a> {
+    /// The name from `x-ms-enum`, or else the value itself.
+    pub fn client_name(&self) -> String {
+        match (self.name, self.value) {
+            (Some(name), _) => name.to_owned(),
+            (None, serde_json::Value::String(value)) => value.clone(),
+            (None, value) => value.to_string(),
+        }
+    }
+}
+
+/// Names converted to identifiers within one scope, such as the fields of a struct or the variants of an enum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameScope {
+    pub case: Case,
+    /// The names that were converted to each identifier, in order.
+    pub idents: IndexMap<String, Vec<String>>,
+}
+
+impl NameScope {
+    pub fn new(case: Case) -> Self {
+        NameScope {
+            case,
+            idents: IndexMap::new(),
+        }
+    }
+
+    /// Converts the name to an identifier, recording it within the scope.
+    pub fn insert(&mut self, name: &str) -> String {
+        let ident = self.case.ident(name);
+        let names = self.idents.entry(ident.clone()).or_default();
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_owned());
+        }
+        ident
+    }
+
+    /// The identifiers that different names were converted to, along with those names.
+    pub fn collisions(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.idents
+            .iter()
+            .filter(|(_, names)| names.len() > 1)
+            .map(|(ident, names)| (ident.as_str(), names.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_names_to_idents() {
+        assert_eq!(snake_case_ident("PrivateClouds_List"), "private_clouds_list");
+        assert_eq!(snake_case_ident("HTTPServer2Id"), "http_server2_id");
+        assert_eq!(snake_case_ident("type"), "type_");
+        assert_eq!(snake_case_ident("$self"), "self_");
+        assert_eq!(snake_case_ident("odata.nextLink"), "odata_next_link");
+        assert_eq!(pascal_case_ident("x-ms-client-request-id"), "XMsClientRequestId");
+        assert_eq!(pascal_case_ident("2019-01-01"), "_20190101");
+        assert_eq!(pascal_case_ident("self"), "Self_");
+        assert_eq!(screaming_snake_case_ident("api-version"), "API_VERSION");
+        assert_eq!(snake_case_ident("--"), "value");
+        assert_eq!(pascal_case_ident("+"), "Value");
+
+        assert_eq!(split_operation_id("PrivateClouds_List"), (Some("PrivateClouds"), "List"));
+        assert_eq!(split_operation_id("ListOperations"), (None, "ListOperations"));
+
+        let parameter: Parameter =
+            serde_json::from_value(json!({ "name": "$top", "in": "query", "type": "integer", "x-ms-client-name": "Top" })).unwrap();
+        assert_eq!(snake_case_ident(parameter.client_name()), "top");
+        let parameter: Parameter = serde_json::from_value(json!({
+            "name": "parameters",
+            "in": "body",
+            "x-ms-client-name": "Params",
+            "schema": { "$ref": "#/definitions/Pet" }
+        }))
+        .unwrap();
+        assert_eq!(snake_case_ident(parameter.client_name()), "params");
+    }
+
+    #[test]
+    fn finds_collisions() {
+        let mut scope = NameScope::new(Case::Snake);
+        for name in ["vmSize", "vm-size", "VMSize", "name", "name", "-", "+"] {
+            scope.insert(name);
+        }
+        let collisions: Vec<_> = scope.collisions().collect();
+        assert_eq!(collisions.len(), 2);
+        assert_eq!(collisions[0].0, "vm_size");
+        assert_eq!(collisions[0].1, ["vmSize", "vm-size", "VMSize"]);
+        assert_eq!(collisions[1].0, "value");
+        assert_eq!(collisions[1].1, ["-", "+"]);
+    }
+}
@@ -52,3 +52,59 @@ pub struct OpenAPI {
     #[serde(rename = "x-ms-parameterized-host", skip_serializing_if = "Option::is_none")]
     pub x_ms_parameterized_host: Option<MsParameterizedHost>,
 }
+
+impl OpenAPI {
+    /// Returns all operations in `paths` followed by those in `x-ms-paths`.
+    /// Path items that are references are skipped.
+    pub fn operations(&self) -> impl Iterator<Item = PathOperation<'_>> {
+        let paths = self.paths.iter().map(|(path, item)| (path, item, false));
+        let x_ms_paths = self.x_ms_paths.iter().map(|(path, item)| (path, item, true));
+        paths.chain(x_ms_paths).flat_map(|(path, item, x_ms_path)| {
+            let methods = match item {
+                ReferenceOr::Item(item) => item.methods().collect(),
+                ReferenceOr::Reference { .. } => Vec::new(),
+            };
+            methods.into_iter().map(move |(method, operation)| PathOperation {
+                path,
+                method,
+                operation,
+                x_ms_path,
+            })
+        })
+    }
+
+    /// Indexes the operations by `operationId`. Operations without an id are not included.
+    /// When an id is used more than once, the first operation wins.
+    pub fn operation_index(&self) -> IndexMap<&str, PathOperation<'_>> {
+        let mut index = IndexMap::new();
+        for operation in self.operations() {
+            if let Some(operation_id) = &operation.operation.operation_id {
+                index.entry(operation_id.as_str()).or_insert(operation);
+            }
+        }
+        index
+    }
+
+    /// Resolves a schema, following local `#/definitions/` references.
+    /// Returns `None` for external references or ones that do not exist.
+    pub fn resolve_schema<'a>(&'a self, schema: &'a ReferenceOr<Schema>) -> Option<&'a Schema> {
+        let mut schema = schema;
+        // guards against definitions that only refer to each other
+        for _ in 0..=self.definitions.len() {
+            match schema {
+                ReferenceOr::Item(schema) => return Some(schema),
+                ReferenceOr::Reference { reference, .. } => schema = self.definitions.get(reference.local_name("definitions")?)?,
+            }
+        }
+        None
+    }
+
+    /// Resolves a parameter, following local `#/parameters/` references.
+    /// Returns `None` for external references or ones that do not exist.
+    pub fn resolve_parameter<'a>(&'a self, parameter: &'a ReferenceOr<Parameter>) -> Option<&'a Parameter> {
+        match parameter {
+            ReferenceOr::Item(parameter) => Some(parameter),
+            ReferenceOr::Reference { reference, .. } => self.parameters.get(reference.local_name("parameters")?),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Describes Rust types as schemas, so that a document can be published from the models of a service
+//! The trait may be derived with `#[derive(OpenApiSchema)]` behind the `derive` feature.
+
+use crate::*;
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
+
+pub type Definitions = IndexMap<String, ReferenceOr<Schema>>;
+
+/// A type that can be described by a schema.
+pub trait OpenApiSchema {
+    /// The name of the definition for this type, or `None` if its schema is written inline where it is used.
+    fn definition_name() -> Option<String> {
+        None
+    }
+
+    /// The schema of the type. Any types it uses that have a definition name are added to `definitions`.
+    fn schema(definitions: &mut Definitions) -> Schema;
+
+    /// The schema to use where the type is used, which is a reference to its definition if it has a definition name.
+    fn schema_ref(definitions: &mut Definitions) -> ReferenceOr<Schema> {
+        match Self::definition_name() {
+            Some(name) => {
+                if !definitions.contains_key(&name) {
+                    // keeps the place of the definition and stops recursive types from being visited again
+                    definitions.insert(name.clone(), Schema::default().into());
+                    let schema = Self::schema(definitions);
+                    definitions.insert(name.clone(), schema.into());
+                }
+                Schema::reference(&name)
+            }
+            None => Self::schema(definitions).into(),
+        }
+    }
+
+    /// true when a property of this type may be missing, such as an `Option`
+    fn optional() -> bool {
+        false
+    }
+}
+
+impl OpenAPI {
+    /// Adds the definition of a type along with the definitions of the types it uses.
+    /// Types without a definition name only add the definitions of the types they use.
+    pub fn definitions_of<T: OpenApiSchema>(mut self) -> Self {
+        T::schema_ref(&mut self.definitions);
+        self
+    }
+}
+
+macro_rules! primitive {
+    ($schema:expr, $($ty:ty),+) => {
+        $(
+            impl OpenApiSchema for $ty {
+                fn schema(_definitions: &mut Definitions) -> Schema {
+                    $schema
+                }
+            }
+        )+
+    };
+}
+
+primitive!(Schema::string(), String, str, char);
+primitive!(Schema::boolean(), bool);
+primitive!(Schema::integer().format("int32"), i8, i16, i32, u8, u16);
+primitive!(Schema::integer().format("int64"), i64, u32, u64, i128, u128, isize, usize);
+primitive!(Schema::number().format("float"), f32);
+primitive!(Schema::number().format("double"), f64);
+primitive!(Schema::default(), Value);
+
+macro_rules! wrapper {
+    ($($ty:ident),+) => {
+        $(
+            impl<T: OpenApiSchema + ?Sized> OpenApiSchema for $ty<T> {
+                fn definition_name() -> Option<String> {
+                    T::definition_name()
+                }
+
+                fn schema(definitions: &mut Definitions) -> Schema {
+                    T::schema(definitions)
+                }
+
+                fn schema_ref(definitions: &mut Definitions) -> ReferenceOr<Schema> {
+                    T::schema_ref(definitions)
+                }
+            }
+        )+
+    };
+}
+
+wrapper!(Box, Rc, Arc);
+
+impl<T: OpenApiSchema + ?Sized> OpenApiSchema for &T {
+    fn definition_name() -> Option<String> {
+        T::definition_name()
+    }
+
+    fn schema(definitions: &mut Definitions) -> Schema {
+        T::schema(definitions)
+    }
+
+    fn schema_ref(definitions: &mut Definitions) -> ReferenceOr<Schema> {
+        T::schema_ref(definitions)
+    }
+}
+
+impl<T: OpenApiSchema + ToOwned + ?Sized> OpenApiSchema for Cow<'_, T> {
+    fn definition_name() -> Option<String> {
+        T::definition_name()
+    }
+
+    fn schema(definitions: &mut Definitions) -> Schema {
+        T::schema(definitions)
+    }
+
+    fn schema_ref(definitions: &mut Definitions) -> ReferenceOr<Schema> {
+        T::schema_ref(definitions)
+    }
+}
+
+impl<T: OpenApiSchema> OpenApiSchema for Option<T> {
+    fn definition_name() -> Option<String> {
+        T::definition_name()
+    }
+
+    fn schema(definitions: &mut Definitions) -> Schema {
+        T::schema(definitions)
+    }
+
+    fn schema_ref(definitions: &mut Definitions) -> ReferenceOr<Schema> {
+        T::schema_ref(definitions)
+    }
+
+    fn optional() -> bool {
+        true
+    }
+}
+
+macro_rules! array {
+    ($unique:expr, $($ty:ident),+) => {
+        $(
+            impl<T: OpenApiSchema> OpenApiSchema for $ty<T> {
+                fn schema(definitions: &mut Definitions) -> Schema {
+                    let mut schema = Schema::array(T::schema_ref(definitions));
+                    if $unique {
+                        schema.common.unique_items = Some(true);
+                    }
+                    schema
+                }
+            }
+        )+
+    };
+}
+
+array!(false, Vec);
+array!(true, HashSet, BTreeSet);
+
+impl<T: OpenApiSchema> OpenApiSchema for [T] {
+    fn schema(definitions: &mut Definitions) -> Schema {
+        Schema::array(T::schema_ref(definitions))
+    }
+}
+
+macro_rules! map {
+    ($($ty:ident),+) => {
+        $(
+            impl<K, V: OpenApiSchema> OpenApiSchema for $ty<K, V> {
+                fn schema(definitions: &mut Definitions) -> Schema {
+                    Schema::map(V::schema_ref(definitions))
+                }
+            }
+        )+
+    };
+}
+
+map!(HashMap, BTreeMap, IndexMap);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Node;
+
+    impl OpenApiSchema for Node {
+        fn definition_name() -> Option<String> {
+            Some("Node".to_owned())
+        }
+
+        fn schema(definitions: &mut Definitions) -> Schema {
+            Schema::object()
+                .property("name", String::schema_ref(definitions))
+                .property("children", Vec::<Node>::schema_ref(definitions))
+                .property("tags", Option::<HashMap<String, Vec<i64>>>::schema_ref(definitions))
+        }
+    }
+
+    #[test]
+    fn describes_recursive_types() {
+        let spec = OpenAPI::new("Nodes", "1").definitions_of::<Node>();
+        assert_eq!(
+            serde_json::to_value(&spec.definitions).unwrap(),
+            json!({
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "children": { "type": "array", "items": { "$ref": "#/definitions/Node" } },
+                        "tags": {
+                            "type": "object",
+                            "additionalProperties": { "type": "array", "items": { "type": "integer", "format": "int64" } }
+                        }
+                    }
+                }
+            })
+        );
+        assert!(Option::<Node>::optional());
+    }
+}
@@ -47,3 +47,61 @@ pub struct Operation {
     #[serde(rename = "x-ms-odata", skip_serializing_if = "Option::is_none")]
     pub x_ms_odata: Option<String>,
 }
+
+impl Response {
+    /// Whether the response describes an error, either by its status code or by `x-ms-error-response`.
+    pub fn is_error(&self, status_code: &StatusCode) -> bool {
+        self.x_ms_error_response.unwrap_or_else(|| status_code.is_error())
+    }
+}
+
+impl Operation {
+    /// The `2XX` responses that are not marked with `x-ms-error-response`.
+    pub fn success_responses(&self) -> impl Iterator<Item = (&StatusCode, &Response)> {
+        self.responses
+            .iter()
+            .filter(|(status_code, response)| status_code.is_success() && !response.is_error(status_code))
+    }
+
+    /// The `4XX`, `5XX` and `default` responses, along with any marked with `x-ms-error-response`.
+    pub fn error_responses(&self) -> impl Iterator<Item = (&StatusCode, &Response)> {
+        self.responses
+            .iter()
+            .filter(|(status_code, response)| response.is_error(status_code))
+    }
+
+    /// The schema of the success response with the lowest status code that has one.
+    /// Exact codes come before ranges, so `200` wins over `201` and `2XX`.
+    pub fn success_schema(&self) -> Option<&ReferenceOr<Schema>> {
+        self.success_responses()
+            .filter_map(|(status_code, response)| response.schema.as_ref().map(|schema| (status_code, schema)))
+            .min_by_key(|(status_code, _)| *status_code)
+            .map(|(_, schema)| schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_responses() {
+        let operation: Operation = serde_json::from_str(
+            r##"{
+                "responses": {
+                    "202": { "description": "Accepted" },
+                    "2XX": { "schema": { "$ref": "#/definitions/Any" } },
+                    "201": { "schema": { "$ref": "#/definitions/Created" } },
+                    "200": { "schema": { "$ref": "#/definitions/Error" }, "x-ms-error-response": true },
+                    "404": { "x-ms-error-response": false },
+                    "default": { "schema": { "$ref": "#/definitions/Error" } }
+                }
+            }"##,
+        )
+        .unwrap();
+        let codes = |responses: Vec<(&StatusCode, &Response)>| responses.into_iter().map(|(code, _)| code.to_string()).collect::<Vec<_>>();
+        assert_eq!(codes(operation.success_responses().collect()), ["202", "2XX", "201"]);
+        assert_eq!(codes(operation.error_responses().collect()), ["200", "default"]);
+        assert_eq!(operation.success_schema(), Some(&Schema::reference("Created")));
+    }
+}
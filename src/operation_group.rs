@@ -0,0 +1,170 @@
+//! Groups the operations the way AutoRest lays out clients
+//! An `operationId` such as `PrivateClouds_List` is the `List` operation of the `PrivateClouds` group.
+//! An `operationId` without an underscore falls back to the first tag.
+
+use crate::*;
+use std::fmt;
+
+/// Operations sharing a client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperationGroup<'a> {
+    /// The group name as first seen, or `None` for operations on the root client.
+    pub name: Option<&'a str>,
+    pub operations: Vec<GroupedOperation<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupedOperation<'a> {
+    /// The part of the `operationId` after the group.
+    pub name: &'a str,
+    pub operation: PathOperation<'a>,
+}
+
+/// The operation groups of a document, along with problems grouping them.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct OperationGroups<'a> {
+    pub groups: Vec<OperationGroup<'a>>,
+    pub errors: Vec<OperationGroupError>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OperationGroupError {
+    /// the operation has no `operationId`, so it is not in any group
+    MissingOperationId { pointer: String },
+    /// the group name differs only in case from one seen before, which it is grouped with
+    InconsistentCasing { group: String, name: String, pointer: String },
+}
+
+impl fmt::Display for OperationGroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationGroupError::MissingOperationId { pointer } => write!(f, "{}: no operationId", pointer),
+            OperationGroupError::InconsistentCasing { group, name, pointer } => {
+                write!(f, "{}: operation group '{}' differs in case from '{}'", pointer, name, group)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OperationGroupError {}
+
+impl OpenAPI {
+    /// Groups the operations in `paths` and `x-ms-paths` by the prefix of their `operationId`, or else their first tag.
+    /// Group names are compared ignoring case, as AutoRest does.
+    pub fn operation_groups(&self) -> OperationGroups<'_> {
+        let mut groups = OperationGroups::default();
+        for operation in self.operations() {
+            let operation_id = match &operation.operation.operation_id {
+                Some(operation_id) => operation_id.as_str(),
+                None => {
+                    groups.errors.push(OperationGroupError::MissingOperationId {
+                        pointer: operation.pointer(),
+                    });
+                    continue;
+                }
+            };
+            let (group, name) = match split_operation_id(operation_id) {
+                (Some(group), name) => (Some(group), name),
+                (None, name) => (operation.operation.tags.first().map(String::as_str), name),
+            };
+            let index = groups.groups.iter().position(|existing| match (existing.name, group) {
+                (Some(existing), Some(group)) => existing.eq_ignore_ascii_case(group),
+                (existing, group) => existing == group,
+            });
+            let index = match index {
+                Some(index) => {
+                    if let (Some(existing), Some(group)) = (groups.groups[index].name, group) {
+                        if existing != group {
+                            groups.errors.push(OperationGroupError::InconsistentCasing {
+                                group: existing.to_owned(),
+                                name: group.to_owned(),
+                                pointer: operation.pointer(),
+                            });
+                        }
+                    }
+                    index
+                }
+                None => {
+                    groups.groups.push(OperationGroup {
+                        name: group,
+                        operations: Vec::new(),
+                    });
+                    groups.groups.len() - 1
+                }
+            };
+            groups.groups[index].operations.push(GroupedOperation { name, operation });
+        }
+        groups
+    }
+}
+
+impl<'a> OperationGroups<'a> {
+    /// Finds a group by name, ignoring case.
+    pub fn group(&self, name: &str) -> Option<&OperationGroup<'a>> {
+        self.groups
+            .iter()
+            .find(|group| group.name.is_some_and(|group| group.eq_ignore_ascii_case(name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_operations() {
+        let spec: OpenAPI = serde_json::from_str(
+            r##"{
+                "swagger": "2.0",
+                "info": { "title": "AVS", "version": "1" },
+                "paths": {
+                    "/privateClouds": {
+                        "get": { "operationId": "PrivateClouds_List", "responses": {} },
+                        "put": { "operationId": "privateClouds_CreateOrUpdate", "responses": {} }
+                    },
+                    "/operations": {
+                        "get": { "operationId": "ListOperations", "tags": ["Operations"], "responses": {} }
+                    },
+                    "/check": {
+                        "post": { "operationId": "CheckQuota", "responses": {} },
+                        "get": { "responses": {} }
+                    }
+                },
+                "x-ms-paths": {
+                    "/privateClouds?api=old": {
+                        "delete": { "operationId": "PrivateClouds_Delete", "responses": {} }
+                    }
+                }
+            }"##,
+        )
+        .unwrap();
+        let groups = spec.operation_groups();
+        let layout: Vec<(Option<&str>, Vec<&str>)> = groups
+            .groups
+            .iter()
+            .map(|group| (group.name, group.operations.iter().map(|operation| operation.name).collect()))
+            .collect();
+        assert_eq!(
+            layout,
+            [
+                (Some("PrivateClouds"), vec!["List", "CreateOrUpdate", "Delete"]),
+                (Some("Operations"), vec!["ListOperations"]),
+                (None, vec!["CheckQuota"]),
+            ]
+        );
+        assert!(groups.group("privateclouds").unwrap().operations[2].operation.x_ms_path);
+        assert_eq!(
+            groups.errors,
+            [
+                OperationGroupError::InconsistentCasing {
+                    group: "PrivateClouds".to_owned(),
+                    name: "privateClouds".to_owned(),
+                    pointer: "/paths/~1privateClouds/put".to_owned(),
+                },
+                OperationGroupError::MissingOperationId {
+                    pointer: "/paths/~1check/get".to_owned(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Pagination model derived from `x-ms-pageable`
+//! https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-pageable
+
+use crate::*;
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::{collections::VecDeque, fmt};
+
+/// The default `itemName` when `x-ms-pageable` does not specify one.
+pub const DEFAULT_ITEM_NAME: &str = "value";
+
+/// How to page through the results of an operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pageable<'a> {
+    /// The operation that returns the first page.
+    pub operation: PathOperation<'a>,
+    /// The name of the property holding the items of a page.
+    pub item_name: &'a str,
+    /// The schema of the item array property.
+    pub items: &'a ReferenceOr<Schema>,
+    /// The schema of each item in the array.
+    pub item_schema: Option<&'a ReferenceOr<Schema>>,
+    /// The name of the property holding the link to the next page, or `None` when there is only one page.
+    pub next_link_name: Option<&'a str>,
+    /// The operation to call for subsequent pages. When `operationName` is not specified, this is the operation itself.
+    /// It is `None` when there is no next link.
+    pub next_operation: Option<PathOperation<'a>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PageableError {
+    /// the operation does not have a success response with a schema
+    MissingResponseSchema { operation: String },
+    /// the schema could not be resolved within this document
+    UnresolvedSchema { operation: String },
+    /// the response schema does not have the item property
+    MissingItemProperty { operation: String, item_name: String },
+    /// `operationName` does not match any `operationId`
+    UnresolvedOperation { operation: String, operation_name: String },
+}
+
+impl fmt::Display for PageableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PageableError::MissingResponseSchema { operation } => write!(f, "{}: no success response with a schema", operation),
+            PageableError::UnresolvedSchema { operation } => write!(f, "{}: unable to resolve the response schema", operation),
+            PageableError::MissingItemProperty { operation, item_name } => {
+                write!(f, "{}: response schema has no item property '{}'", operation, item_name)
+            }
+            PageableError::UnresolvedOperation { operation, operation_name } => {
+                write!(f, "{}: next page operation '{}' not found", operation, operation_name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PageableError {}
+
+impl OpenAPI {
+    /// Computes the pagination descriptor of every operation with `x-ms-pageable`.
+    pub fn pageables(&self) -> Vec<Result<Pageable<'_>, PageableError>> {
+        let index = self.operation_index();
+        self.operations()
+            .filter_map(|operation| self.pageable_with_index(operation, &index))
+            .collect()
+    }
+
+    /// Computes the pagination descriptor of an operation, or `None` when it is not pageable.
+    pub fn pageable<'a>(&'a self, operation: PathOperation<'a>) -> Option<Result<Pageable<'a>, PageableError>> {
+        self.pageable_with_index(operation, &self.operation_index())
+    }
+
+    fn pageable_with_index<'a>(
+        &'a self,
+        operation: PathOperation<'a>,
+        index: &IndexMap<&str, PathOperation<'a>>,
+    ) -> Option<Result<Pageable<'a>, PageableError>> {
+        let pageable = operation.operation.x_ms_pageable.as_ref()?;
+        let name = || display_name(&operation);
+        let item_name = pageable.item_name.as_deref().unwrap_or(DEFAULT_ITEM_NAME);
+        Some((|| {
+            let schema = operation
+                .operation
+                .success_schema()
+                .ok_or_else(|| PageableError::MissingResponseSchema { operation: name() })?;
+            let schema = self
+                .resolve_schema(schema)
+                .ok_or_else(|| PageableError::UnresolvedSchema { operation: name() })?;
+            let items = self
+                .find_property(schema, item_name)
+                .ok_or_else(|| PageableError::MissingItemProperty {
+                    operation: name(),
+                    item_name: item_name.to_owned(),
+                })?;
+            let item_schema = self.resolve_schema(items).and_then(|items| items.common.items.as_ref().as_ref());
+            let next_link_name = pageable.next_link_name.as_deref();
+            let next_operation = match (next_link_name, &pageable.operation_name) {
+                (None, _) => None,
+                (Some(_), None) => Some(operation),
+                (Some(_), Some(operation_name)) => {
+                    Some(
+                        find_operation(index, operation_name).ok_or_else(|| PageableError::UnresolvedOperation {
+                            operation: name(),
+                            operation_name: operation_name.clone(),
+                        })?,
+                    )
+                }
+            };
+            Ok(Pageable {
+                operation,
+                item_name,
+                items,
+                item_schema,
+                next_link_name,
+                next_operation,
+            })
+        })())
+    }
+
+    /// Finds a property of a schema, including those inherited through `allOf`.
+    fn find_property<'a>(&'a self, schema: &'a Schema, name: &str) -> Option<&'a ReferenceOr<Schema>> {
+        if let Some(property) = schema.properties.get(name) {
+            return Some(property);
+        }
+        schema
+            .all_of
+            .iter()
+            .filter_map(|parent| self.resolve_schema(parent))
+            .find_map(|parent| self.find_property(parent, name))
+    }
+}
+
+fn display_name(operation: &PathOperation) -> String {
+    match &operation.operation.operation_id {
+        Some(operation_id) => operation_id.clone(),
+        None => format!("{} {}", operation.method, operation.path),
+    }
+}
+
+/// AutoRest matches `operationName` against `operationId`, ignoring case.
+fn find_operation<'a>(index: &IndexMap<&str, PathOperation<'a>>, operation_name: &str) -> Option<PathOperation<'a>> {
+    index.get(operation_name).copied().or_else(|| {
+        index
+            .iter()
+            .find(|(operation_id, _)| operation_id.eq_ignore_ascii_case(operation_name))
+            .map(|(_, operation)| *operation)
+    })
+}
+
+impl<'a> Pageable<'a> {
+    /// Creates an iterator over the items of every page.
+    /// `fetch` is called with `None` for the first page and with the next link for each page after that.
+    pub fn pager<F, E>(&self, fetch: F) -> Pager<F>
+    where
+        F: FnMut(Option<&str>) -> Result<Value, E>,
+    {
+        Pager::new(self.item_name, self.next_link_name, fetch)
+    }
+}
+
+/// Iterates the items across pages of JSON responses.
+pub struct Pager<F> {
+    fetch: F,
+    item_name: String,
+    next_link_name: Option<String>,
+    next_link: Option<String>,
+    items: VecDeque<Value>,
+    started: bool,
+    done: bool,
+}
+
+impl<F> Pager<F> {
+    pub fn new(item_name: &str, next_link_name: Option<&str>, fetch: F) -> Self {
+        Pager {
+            fetch,
+            item_name: item_name.to_owned(),
+            next_link_name: next_link_name.map(str::to_owned),
+            next_link: None,
+            items: VecDeque::new(),
+            started: false,
+            done: false,
+        }
+    }
+
+    fn read_page(&mut self, mut page: Value) {
+        if let Some(Value::Array(items)) = page.get_mut(&self.item_name).map(Value::take) {
+            self.items.extend(items);
+        }
+        self.next_link = match &self.next_link_name {
+            Some(next_link_name) => match page.get(next_link_name) {
+                Some(Value::String(next_link)) if !next_link.is_empty() => Some(next_link.clone()),
+                _ => None,
+            },
+            None => None,
+        };
+    }
+}
+
+impl<F, E> Iterator for Pager<F>
+where
+    F: FnMut(Option<&str>) -> Result<Value, E>,
+{
+    type Item = Result<Value, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.pop_front() {
+                return Some(Ok(item));
+            }
+            if self.done || (self.started && self.next_link.is_none()) {
+                return None;
+            }
+            self.started = true;
+            match (self.fetch)(self.next_link.as_deref()) {
+                Ok(page) => self.read_page(page),
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> OpenAPI {
+        serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": {
+                "/clouds": {
+                    "get": {
+                        "operationId": "PrivateClouds_List",
+                        "x-ms-pageable": { "nextLinkName": "nextLink", "operationName": "privateclouds_listnext" },
+                        "responses": { "200": { "schema": { "$ref": "#/definitions/PrivateCloudList" } } }
+                    }
+                },
+                "/clouds/next": {
+                    "get": {
+                        "operationId": "PrivateClouds_ListNext",
+                        "responses": { "200": { "schema": { "$ref": "#/definitions/PrivateCloudList" } } }
+                    }
+                },
+                "/clouds/all": {
+                    "get": {
+                        "operationId": "PrivateClouds_ListAll",
+                        "x-ms-pageable": { "nextLinkName": null },
+                        "responses": { "200": { "schema": { "$ref": "#/definitions/PrivateCloudList" } } }
+                    }
+                }
+            },
+            "definitions": {
+                "PrivateCloudList": {
+                    "allOf": [{ "$ref": "#/definitions/List" }],
+                    "properties": { "nextLink": { "type": "string" } }
+                },
+                "List": {
+                    "properties": { "value": { "type": "array", "items": { "$ref": "#/definitions/PrivateCloud" } } }
+                },
+                "PrivateCloud": { "properties": { "id": { "type": "string" } } }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_next_operation_and_items() {
+        let spec = spec();
+        let pageables: Vec<_> = spec.pageables().into_iter().map(Result::unwrap).collect();
+        assert_eq!(pageables.len(), 2);
+
+        let list = &pageables[0];
+        assert_eq!(list.item_name, "value");
+        assert_eq!(list.next_link_name, Some("nextLink"));
+        assert_eq!(list.next_operation.unwrap().path, "/clouds/next");
+        let item_schema = list.item_schema.unwrap();
+        assert!(matches!(item_schema, ReferenceOr::Reference { reference, .. } if reference.name.as_deref() == Some("PrivateCloud")));
+
+        let list_all = &pageables[1];
+        assert_eq!(list_all.next_link_name, None);
+        assert_eq!(list_all.next_operation, None);
+    }
+
+    #[test]
+    fn pager_follows_next_links() {
+        let spec = spec();
+        let pageable = spec.pageables().remove(0).unwrap();
+        let mut links = Vec::new();
+        let items = pageable
+            .pager(|next_link: Option<&str>| {
+                links.push(next_link.map(str::to_owned));
+                Ok::<_, ()>(match next_link {
+                    None => json!({ "value": [1, 2], "nextLink": "page2" }),
+                    Some(_) => json!({ "value": [3], "nextLink": null }),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(items, vec![json!(1), json!(2), json!(3)]);
+        assert_eq!(links, vec![None, Some("page2".to_owned())]);
+    }
+}
@@ -1,36 +1,29 @@
-use crate::{MsParameterGrouping, ReferenceOr, Schema, SchemaCommon};
-use serde::{Deserialize, Serialize};
+use crate::{next_value, FieldsVisitor, MsParameterGrouping, MsParameterLocation, ReadFields, ReferenceOr, Schema, SchemaCommon};
+use indexmap::IndexMap;
+use serde::{
+    de::{Error, MapAccess},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
 
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#parameter-object
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Parameter {
-    #[serde(flatten)]
-    pub common: SchemaCommon,
-
     /// The name of the parameter.
     pub name: String,
 
-    /// may be `header`, `query`, 'path`, `formData`
-    #[serde(rename = "in")]
-    pub in_: ParameterType,
+    /// where the parameter is, along with the fields that depend on it
+    #[serde(flatten)]
+    pub kind: ParameterKind,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub schema: Option<ReferenceOr<Schema>>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub allow_empty_value: Option<bool>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub collection_format: Option<CollectionFormat>,
-
     /// provides a mechanism to specify that the global parameter is actually a parameter on the operation and not a client property
     /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-parameter-location
     #[serde(rename = "x-ms-parameter-location", skip_serializing_if = "Option::is_none")]
-    pub x_ms_parameter_location: Option<String>,
+    pub x_ms_parameter_location: Option<MsParameterLocation>,
 
     /// skips URL encoding for path and query parameters
     /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-skip-url-encoding
@@ -46,14 +39,63 @@ pub struct Parameter {
     pub x_ms_client_request_id: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+/// A body parameter is described by a schema. The others are described by `type`, `format`, `items` and validations.
+/// Deserializing reads the fields of both, then keeps those for the value of `in`, wherever it is in the object.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(tag = "in", rename_all = "camelCase")]
+#[allow(clippy::large_enum_variant)]
+pub enum ParameterKind {
+    Body(BodyParameter),
+    Path(NonBodyParameter),
+    Query(NonBodyParameter),
+    Header(NonBodyParameter),
+    FormData(NonBodyParameter),
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BodyParameter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub schema: ReferenceOr<Schema>,
+
+    /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-client-name
+    #[serde(rename = "x-ms-client-name", skip_serializing_if = "Option::is_none")]
+    pub x_ms_client_name: Option<String>,
+
+    /// flattens the properties of the body into parameters of the method
+    /// https://github.com/Azure/autorest/blob/master/docs/extensions/readme.md#x-ms-client-flatten
+    #[serde(rename = "x-ms-client-flatten", skip_serializing_if = "Option::is_none")]
+    pub x_ms_client_flatten: Option<bool>,
+
+    /// The fields of other parameters, such as `type`, which do not belong on a body parameter but are kept as written.
+    #[serde(flatten)]
+    pub extra: IndexMap<String, Value>,
+}
+
+/// A `path`, `query`, `header` or `formData` parameter. Only `formData` parameters may have the `file` type.
+#[derive(Clone, Debug, Serialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NonBodyParameter {
+    #[serde(flatten)]
+    pub common: SchemaCommon,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_empty_value: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_format: Option<CollectionFormat>,
+}
+
+/// The values of `in`.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub enum ParameterType {
     Path,
     Query,
     Header,
     Body,
-    Form,
+    FormData,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -65,3 +107,238 @@ pub enum CollectionFormat {
     Pipes,
     Multi,
 }
+
+/// The fields of a parameter, read before it is known where the parameter is.
+#[derive(Default)]
+struct ParameterFields {
+    name: Option<String>,
+    in_: Option<ParameterType>,
+    required: Option<bool>,
+    x_ms_parameter_location: Option<MsParameterLocation>,
+    x_ms_skip_url_encoding: Option<bool>,
+    x_ms_parameter_grouping: Option<MsParameterGrouping>,
+    x_ms_client_request_id: Option<bool>,
+    schema: Option<ReferenceOr<Schema>>,
+    x_ms_client_flatten: Option<bool>,
+    non_body: NonBodyParameter,
+}
+
+impl<'de> ReadFields<'de> for ParameterFields {
+    fn read_field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "name" => self.name = next_value(map, key)?,
+            "in" => self.in_ = next_value(map, key)?,
+            "required" => self.required = next_value(map, key)?,
+            "x-ms-parameter-location" => self.x_ms_parameter_location = next_value(map, key)?,
+            "x-ms-skip-url-encoding" => self.x_ms_skip_url_encoding = next_value(map, key)?,
+            "x-ms-parameter-grouping" => self.x_ms_parameter_grouping = next_value(map, key)?,
+            "x-ms-client-request-id" => self.x_ms_client_request_id = next_value(map, key)?,
+            "schema" => self.schema = next_value(map, key)?,
+            "x-ms-client-flatten" => self.x_ms_client_flatten = next_value(map, key)?,
+            _ => return self.non_body.read_field(key, map),
+        }
+        Ok(true)
+    }
+}
+
+impl ParameterFields {
+    fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FieldsVisitor::new("a parameter"))
+    }
+
+    /// Keeps the fields for where the parameter is.
+    fn kind<E: Error>(&mut self) -> Result<ParameterKind, E> {
+        let in_ = self.in_.ok_or_else(|| E::missing_field("in"))?;
+        let non_body = std::mem::take(&mut self.non_body);
+        Ok(match in_ {
+            ParameterType::Body => ParameterKind::Body(self.body(non_body)?),
+            ParameterType::Path => ParameterKind::Path(non_body),
+            ParameterType::Query => ParameterKind::Query(non_body),
+            ParameterType::Header => ParameterKind::Header(non_body),
+            ParameterType::FormData => ParameterKind::FormData(non_body),
+        })
+    }
+
+    fn body<E: Error>(&mut self, mut non_body: NonBodyParameter) -> Result<BodyParameter, E> {
+        let schema = self.schema.take().ok_or_else(|| E::missing_field("schema"))?;
+        let description = non_body.common.description.take();
+        let x_ms_client_name = non_body.common.x_ms_client_name.take();
+        let extra = match serde_json::to_value(&non_body).map_err(E::custom)? {
+            Value::Object(extra) => extra.into_iter().collect(),
+            _ => IndexMap::new(),
+        };
+        Ok(BodyParameter {
+            description,
+            schema,
+            x_ms_client_name,
+            x_ms_client_flatten: self.x_ms_client_flatten.take(),
+            extra,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Parameter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = ParameterFields::read(deserializer)?;
+        let kind = fields.kind()?;
+        Ok(Parameter {
+            name: fields.name.ok_or_else(|| D::Error::missing_field("name"))?,
+            kind,
+            required: fields.required,
+            x_ms_parameter_location: fields.x_ms_parameter_location,
+            x_ms_skip_url_encoding: fields.x_ms_skip_url_encoding,
+            x_ms_parameter_grouping: fields.x_ms_parameter_grouping,
+            x_ms_client_request_id: fields.x_ms_client_request_id,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ParameterKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ParameterFields::read(deserializer)?.kind()
+    }
+}
+
+impl<'de> Deserialize<'de> for BodyParameter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fields = ParameterFields::read(deserializer)?;
+        let non_body = std::mem::take(&mut fields.non_body);
+        fields.body(non_body)
+    }
+}
+
+impl<'de> ReadFields<'de> for NonBodyParameter {
+    fn read_field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "allowEmptyValue" => self.allow_empty_value = next_value(map, key)?,
+            "collectionFormat" => self.collection_format = next_value(map, key)?,
+            _ => return self.common.read_field(key, map),
+        }
+        Ok(true)
+    }
+}
+
+impl<'de> Deserialize<'de> for NonBodyParameter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FieldsVisitor::new("a parameter"))
+    }
+}
+
+impl Parameter {
+    /// Where the parameter is.
+    pub fn in_(&self) -> ParameterType {
+        match &self.kind {
+            ParameterKind::Body(_) => ParameterType::Body,
+            ParameterKind::Path(_) => ParameterType::Path,
+            ParameterKind::Query(_) => ParameterType::Query,
+            ParameterKind::Header(_) => ParameterType::Header,
+            ParameterKind::FormData(_) => ParameterType::FormData,
+        }
+    }
+
+    /// The schema of a body parameter.
+    pub fn body_schema(&self) -> Option<&ReferenceOr<Schema>> {
+        match &self.kind {
+            ParameterKind::Body(body) => Some(&body.schema),
+            _ => None,
+        }
+    }
+
+    /// The `type`, `format`, `items` and validations of a parameter other than a body parameter.
+    pub fn common(&self) -> Option<&SchemaCommon> {
+        self.non_body().map(|parameter| &parameter.common)
+    }
+
+    pub fn non_body(&self) -> Option<&NonBodyParameter> {
+        match &self.kind {
+            ParameterKind::Body(_) => None,
+            ParameterKind::Path(parameter)
+            | ParameterKind::Query(parameter)
+            | ParameterKind::Header(parameter)
+            | ParameterKind::FormData(parameter) => Some(parameter),
+        }
+    }
+
+    pub fn non_body_mut(&mut self) -> Option<&mut NonBodyParameter> {
+        match &mut self.kind {
+            ParameterKind::Body(_) => None,
+            ParameterKind::Path(parameter)
+            | ParameterKind::Query(parameter)
+            | ParameterKind::Header(parameter)
+            | ParameterKind::FormData(parameter) => Some(parameter),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataType;
+    use serde_json::json;
+
+    #[test]
+    fn splits_body_and_other_parameters() {
+        let file = json!({ "name": "file", "in": "formData", "type": "file", "required": true });
+        let parameter: Parameter = serde_json::from_value(file.clone()).unwrap();
+        assert_eq!(parameter.in_(), ParameterType::FormData);
+        assert_eq!(parameter.common().and_then(|common| common.type_.clone()), Some(DataType::File));
+        assert_eq!(serde_json::to_value(&parameter).unwrap(), file);
+
+        let body = json!({
+            "name": "pet",
+            "in": "body",
+            "description": "The pet.",
+            "schema": { "$ref": "#/definitions/Pet" },
+            "x-ms-parameter-location": "method"
+        });
+        let parameter: Parameter = serde_json::from_value(body.clone()).unwrap();
+        assert!(parameter.common().is_none());
+        assert!(parameter.body_schema().is_some());
+        assert_eq!(parameter.x_ms_parameter_location, Some(MsParameterLocation::Method));
+        assert_eq!(serde_json::to_value(&parameter).unwrap(), body);
+
+        let no_schema = json!({ "name": "pet", "in": "body" });
+        assert!(serde_json::from_value::<Parameter>(no_schema).is_err());
+    }
+
+    #[test]
+    fn roundtrips_body_extensions() {
+        let body = json!({
+            "in": "body",
+            "name": "parameters",
+            "x-ms-client-name": "Params",
+            "x-ms-client-flatten": true,
+            "schema": { "$ref": "#/definitions/Pet" }
+        });
+        let parameter: Parameter = serde_json::from_value(body.clone()).unwrap();
+        match &parameter.kind {
+            ParameterKind::Body(body) => {
+                assert_eq!(body.x_ms_client_name.as_deref(), Some("Params"));
+                assert_eq!(body.x_ms_client_flatten, Some(true));
+            }
+            kind => panic!("expected a body parameter: {:?}", kind),
+        }
+        assert_eq!(serde_json::to_value(&parameter).unwrap(), body);
+    }
+
+    #[test]
+    fn keeps_stray_fields_of_body_parameters() {
+        let body = json!({
+            "name": "b",
+            "in": "body",
+            "type": "object",
+            "required": true,
+            "collectionFormat": "csv",
+            "schema": { "type": "object", "properties": { "name": { "type": "string" } } }
+        });
+        let parameter: Parameter = serde_json::from_value(body.clone()).unwrap();
+        match &parameter.kind {
+            ParameterKind::Body(body) => {
+                assert_eq!(body.extra["type"], json!("object"));
+                assert_eq!(body.extra["collectionFormat"], json!("csv"));
+            }
+            kind => panic!("expected a body parameter: {:?}", kind),
+        }
+        assert_eq!(serde_json::to_value(&parameter).unwrap(), body);
+    }
+}
@@ -1,5 +1,6 @@
-use crate::{Operation, Parameter, ReferenceOr};
+use crate::{escape_pointer_token, Operation, Parameter, ReferenceOr};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#path-item-object
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
@@ -25,16 +26,67 @@ pub struct PathItem {
 impl PathItem {
     /// Returns all operations
     pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+        self.methods().map(|(_, operation)| operation)
+    }
+
+    /// Returns all operations along with their HTTP method
+    pub fn methods(&self) -> impl Iterator<Item = (Method, &Operation)> {
         vec![
-            self.get.as_ref(),
-            self.post.as_ref(),
-            self.put.as_ref(),
-            self.patch.as_ref(),
-            self.delete.as_ref(),
-            self.options.as_ref(),
-            self.head.as_ref(),
+            (Method::Get, self.get.as_ref()),
+            (Method::Post, self.post.as_ref()),
+            (Method::Put, self.put.as_ref()),
+            (Method::Patch, self.patch.as_ref()),
+            (Method::Delete, self.delete.as_ref()),
+            (Method::Options, self.options.as_ref()),
+            (Method::Head, self.head.as_ref()),
         ]
         .into_iter()
-        .filter_map(|x| x)
+        .filter_map(|(method, operation)| operation.map(|operation| (method, operation)))
+    }
+}
+
+/// The HTTP methods a Path Item Object may describe.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Options,
+    Head,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Method::Get => "get",
+            Method::Post => "post",
+            Method::Put => "put",
+            Method::Patch => "patch",
+            Method::Delete => "delete",
+            Method::Options => "options",
+            Method::Head => "head",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An operation together with the path it is found at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathOperation<'a> {
+    pub path: &'a str,
+    pub method: Method,
+    pub operation: &'a Operation,
+    /// true when the path comes from `x-ms-paths` instead of `paths`
+    pub x_ms_path: bool,
+}
+
+impl<'a> PathOperation<'a> {
+    /// The JSON Pointer to the operation.
+    pub fn pointer(&self) -> String {
+        let key = if self.x_ms_path { "x-ms-paths" } else { "paths" };
+        format!("/{}/{}/{}", key, escape_pointer_token(self.path), self.method)
     }
 }
@@ -0,0 +1,243 @@
+//! Finds the definitions, parameters and responses that are reachable from the operations
+
+use crate::*;
+use indexmap::{IndexMap, IndexSet};
+use std::{collections::HashSet, fmt};
+
+/// A named definition, parameter or response of a document.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Component {
+    Definition(String),
+    Parameter(String),
+    Response(String),
+}
+
+impl Component {
+    /// Returns the component a local reference points to.
+    pub fn from_reference(reference: &Reference) -> Option<Self> {
+        if let Some(name) = reference.local_name("definitions") {
+            Some(Component::Definition(name.to_owned()))
+        } else if let Some(name) = reference.local_name("parameters") {
+            Some(Component::Parameter(name.to_owned()))
+        } else {
+            reference.local_name("responses").map(|name| Component::Response(name.to_owned()))
+        }
+    }
+
+    /// Returns the component that contains the JSON Pointer, if any.
+    pub fn from_pointer(pointer: &str) -> Option<Self> {
+        let mut tokens = pointer.split('/').skip(1);
+        let section = tokens.next()?;
+        let name = unescape_pointer_token(tokens.next()?);
+        match section {
+            "definitions" => Some(Component::Definition(name)),
+            "parameters" => Some(Component::Parameter(name)),
+            "responses" => Some(Component::Response(name)),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Component::Definition(name) | Component::Parameter(name) | Component::Response(name) => name,
+        }
+    }
+
+    /// The JSON Pointer to the component.
+    pub fn pointer(&self) -> String {
+        let section = match self {
+            Component::Definition(_) => "definitions",
+            Component::Parameter(_) => "parameters",
+            Component::Response(_) => "responses",
+        };
+        format!("/{}/{}", section, escape_pointer_token(self.name()))
+    }
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pointer())
+    }
+}
+
+/// The definitions, parameters and responses that are never referenced.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Unused {
+    pub definitions: Vec<String>,
+    pub parameters: Vec<String>,
+    pub responses: Vec<String>,
+}
+
+impl Unused {
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty() && self.parameters.is_empty() && self.responses.is_empty()
+    }
+}
+
+impl OpenAPI {
+    /// Returns the components directly referenced by each component, and by `None` for those referenced outside of any component,
+    /// such as from the operations.
+    pub fn component_references(&self) -> IndexMap<Option<Component>, IndexSet<Component>> {
+        let mut references: IndexMap<Option<Component>, IndexSet<Component>> = IndexMap::new();
+        self.walk_references(|pointer, reference| {
+            if let Some(target) = Component::from_reference(reference) {
+                references.entry(Component::from_pointer(pointer)).or_default().insert(target);
+            }
+        });
+        references
+    }
+
+    /// Returns the components reachable from the given ones, including themselves.
+    /// Definitions that inherit through `allOf` from a reachable polymorphic definition are reachable too,
+    /// since they may be returned in its place.
+    pub fn reachable(&self, roots: impl IntoIterator<Item = Component>) -> IndexSet<Component> {
+        let references = self.component_references();
+        let mut reachable = IndexSet::new();
+        let mut pending: Vec<Component> = roots.into_iter().collect();
+        loop {
+            while let Some(component) = pending.pop() {
+                if let Some(targets) = references.get(&Some(component.clone())) {
+                    pending.extend(targets.iter().filter(|target| !reachable.contains(*target)).cloned());
+                }
+                reachable.insert(component);
+            }
+            pending.extend(
+                self.definitions
+                    .keys()
+                    .map(|name| Component::Definition(name.clone()))
+                    .filter(|child| {
+                        !reachable.contains(child)
+                            && self
+                                .parents(child.name())
+                                .iter()
+                                .any(|parent| reachable.contains(parent) && self.is_polymorphic(parent.name()))
+                    }),
+            );
+            if pending.is_empty() {
+                return reachable;
+            }
+        }
+    }
+
+    /// Returns the components reachable from the operations.
+    pub fn used(&self) -> IndexSet<Component> {
+        let roots = self.component_references().get(&None).cloned().unwrap_or_default();
+        self.reachable(roots)
+    }
+
+    /// Returns the definitions, parameters and responses not reachable from the operations.
+    pub fn unused(&self) -> Unused {
+        let used = self.used();
+        let unused = |component: Component| {
+            if used.contains(&component) {
+                None
+            } else {
+                Some(component.name().to_owned())
+            }
+        };
+        Unused {
+            definitions: self
+                .definitions
+                .keys()
+                .filter_map(|name| unused(Component::Definition(name.clone())))
+                .collect(),
+            parameters: self
+                .parameters
+                .keys()
+                .filter_map(|name| unused(Component::Parameter(name.clone())))
+                .collect(),
+            responses: self
+                .responses
+                .keys()
+                .filter_map(|status_code| unused(Component::Response(status_code.to_string())))
+                .collect(),
+        }
+    }
+
+    /// Removes the definitions, parameters and responses not reachable from the operations and returns them.
+    pub fn prune(&mut self) -> Unused {
+        let unused = self.unused();
+        let definitions: HashSet<&String> = unused.definitions.iter().collect();
+        let parameters: HashSet<&String> = unused.parameters.iter().collect();
+        let responses: HashSet<&String> = unused.responses.iter().collect();
+        self.definitions.retain(|name, _| !definitions.contains(name));
+        self.parameters.retain(|name, _| !parameters.contains(name));
+        self.responses
+            .retain(|status_code, _| !responses.contains(&status_code.to_string()));
+        unused
+    }
+
+    /// The local definitions a definition inherits from directly.
+    fn parents(&self, name: &str) -> Vec<Component> {
+        match self.definitions.get(name) {
+            Some(ReferenceOr::Item(schema)) => schema
+                .all_of
+                .iter()
+                .filter_map(|parent| match parent {
+                    ReferenceOr::Reference { reference, .. } => Component::from_reference(reference),
+                    ReferenceOr::Item(_) => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns true if the definition or any it inherits from has a `discriminator`.
+    fn is_polymorphic(&self, name: &str) -> bool {
+        let mut visited = IndexSet::new();
+        let mut pending = vec![name.to_owned()];
+        while let Some(name) = pending.pop() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+            if let Some(ReferenceOr::Item(schema)) = self.definitions.get(&name) {
+                if schema.discriminator.is_some() {
+                    return true;
+                }
+            }
+            pending.extend(self.parents(&name).iter().map(|parent| parent.name().to_owned()));
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prunes_unreferenced_components() {
+        let mut spec: OpenAPI = serde_json::from_str(
+            r##"{
+                "swagger": "2.0",
+                "info": {},
+                "paths": {
+                    "/pets": {
+                        "get": {
+                            "parameters": [{ "$ref": "#/parameters/ApiVersion" }],
+                            "responses": { "200": { "schema": { "$ref": "#/definitions/PetList" } } }
+                        }
+                    }
+                },
+                "definitions": {
+                    "PetList": { "properties": { "value": { "type": "array", "items": { "$ref": "#/definitions/Pet" } } } },
+                    "Pet": { "discriminator": "kind", "properties": { "kind": { "type": "string" } } },
+                    "Dog": { "allOf": [{ "$ref": "#/definitions/Pet" }], "properties": { "owner": { "$ref": "#/definitions/Owner" } } },
+                    "Owner": { "type": "object" },
+                    "Old": { "properties": { "owner": { "$ref": "#/definitions/Owner" } } },
+                    "OldChild": { "allOf": [{ "$ref": "#/definitions/Old" }] }
+                },
+                "parameters": {
+                    "ApiVersion": { "name": "api-version", "in": "query", "type": "string" },
+                    "Unused": { "name": "unused", "in": "query", "type": "string" }
+                }
+            }"##,
+        )
+        .unwrap();
+        let unused = spec.prune();
+        assert_eq!(unused.definitions, vec!["Old", "OldChild"]);
+        assert_eq!(unused.parameters, vec!["Unused"]);
+        assert!(spec.unused().is_empty());
+        assert_eq!(spec.definitions.keys().collect::<Vec<_>>(), vec!["PetList", "Pet", "Dog", "Owner"]);
+    }
+}
@@ -0,0 +1,484 @@
+//! Reads the AutoRest configuration from the fenced `yaml` blocks of a literate `readme.md`
+//! https://github.com/Azure/autorest/blob/master/docs/user/literate-file-formats/configuration.md
+
+use crate::{merge_files, Error, Merged, Result};
+use serde_json::{Map, Value};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// The configuration blocks of a `readme.md`, in order.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Readme {
+    pub blocks: Vec<ReadmeBlock>,
+}
+
+/// A fenced `yaml` block, such as one starting with ```` ```yaml $(tag) == 'package-2020-03' ````.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadmeBlock {
+    /// The line the block starts on, counting from 1.
+    pub line: usize,
+    /// The condition after the language, if any.
+    pub condition: Option<String>,
+    pub settings: Map<String, Value>,
+    parsed_condition: Option<Condition>,
+}
+
+/// The configuration that applies for a tag.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TagConfig {
+    pub tag: String,
+    /// The `input-file` entries, relative to the directory of the `readme.md`.
+    pub input_files: Vec<String>,
+    /// The `directive` entries.
+    pub directives: Vec<Value>,
+    /// All of the settings, including the ones above.
+    pub settings: Map<String, Value>,
+}
+
+#[derive(Debug)]
+pub enum ReadmeError {
+    /// the block is not valid YAML
+    Yaml { line: usize, source: serde_yaml::Error },
+    /// the block is not a YAML mapping
+    NotMapping { line: usize },
+    /// the condition of the block could not be parsed
+    Condition { line: usize, condition: String },
+    /// a block is not closed
+    Unclosed { line: usize },
+}
+
+impl fmt::Display for ReadmeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadmeError::Yaml { line, source } => write!(f, "line {}: {}", line, source),
+            ReadmeError::NotMapping { line } => write!(f, "line {}: expected a YAML mapping", line),
+            ReadmeError::Condition { line, condition } => write!(f, "line {}: unable to parse condition {}", line, condition),
+            ReadmeError::Unclosed { line } => write!(f, "line {}: the block is not closed", line),
+        }
+    }
+}
+
+impl std::error::Error for ReadmeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadmeError::Yaml { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Readme {
+    /// Reads a `readme.md` file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let markdown = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        Readme::parse(&markdown).map_err(|source| Error::Readme {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    /// Parses the `yaml` blocks of a markdown document. Blocks in other languages are skipped.
+    pub fn parse(markdown: &str) -> Result<Self, ReadmeError> {
+        let mut blocks = Vec::new();
+        let mut lines = markdown.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let info = match line.trim_start().strip_prefix("```") {
+                Some(info) => info.trim(),
+                None => continue,
+            };
+            let line = index + 1;
+            let mut content = String::new();
+            loop {
+                match lines.next() {
+                    Some((_, text)) if text.trim() == "```" => break,
+                    Some((_, text)) => {
+                        content.push_str(text);
+                        content.push('\n');
+                    }
+                    None => return Err(ReadmeError::Unclosed { line }),
+                }
+            }
+            let (language, condition) = match info.find(char::is_whitespace) {
+                Some(end) => (&info[..end], Some(info[end..].trim().to_owned())),
+                None => (info, None),
+            };
+            if language != "yaml" {
+                continue;
+            }
+            let parsed_condition = match &condition {
+                Some(condition) => Some(Condition::parse(condition).ok_or_else(|| ReadmeError::Condition {
+                    line,
+                    condition: condition.clone(),
+                })?),
+                None => None,
+            };
+            let settings = match serde_yaml::from_str(&content).map_err(|source| ReadmeError::Yaml { line, source })? {
+                Value::Object(settings) => settings,
+                Value::Null => Map::new(),
+                _ => return Err(ReadmeError::NotMapping { line }),
+            };
+            blocks.push(ReadmeBlock {
+                line,
+                condition,
+                settings,
+                parsed_condition,
+            });
+        }
+        Ok(Readme { blocks })
+    }
+
+    /// Returns the settings that apply for the given arguments, such as `tag` or `python`.
+    /// Blocks are applied in order, so a block may depend on a setting from an earlier one.
+    /// Arguments and earlier values win over later ones for the same setting, except for lists, which are combined.
+    pub fn evaluate(&self, args: &Map<String, Value>) -> Map<String, Value> {
+        let mut settings = args.clone();
+        for block in &self.blocks {
+            if block.applies(&settings) {
+                merge_settings(&mut settings, &block.settings);
+            }
+        }
+        settings
+    }
+
+    /// The tag used when none is given.
+    pub fn default_tag(&self) -> Option<String> {
+        self.evaluate(&Map::new()).get("tag").and_then(Value::as_str).map(str::to_owned)
+    }
+
+    /// The tags compared against in the block conditions, in order of appearance.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags = Vec::new();
+        for condition in self.blocks.iter().filter_map(|block| block.parsed_condition.as_ref()) {
+            condition.tags(&mut tags);
+        }
+        tags
+    }
+
+    /// Returns the configuration for a tag.
+    pub fn tag(&self, tag: &str) -> TagConfig {
+        let mut args = Map::new();
+        args.insert("tag".to_owned(), Value::String(tag.to_owned()));
+        let settings = self.evaluate(&args);
+        let input_files = match settings.get("input-file") {
+            Some(Value::String(file)) => vec![file.clone()],
+            Some(Value::Array(files)) => files.iter().filter_map(Value::as_str).map(str::to_owned).collect(),
+            _ => Vec::new(),
+        };
+        let directives = match settings.get("directive") {
+            Some(Value::Array(directives)) => directives.clone(),
+            Some(directive) => vec![directive.clone()],
+            None => Vec::new(),
+        };
+        TagConfig {
+            tag: tag.to_owned(),
+            input_files,
+            directives,
+            settings,
+        }
+    }
+}
+
+impl ReadmeBlock {
+    /// Returns true if the block has no condition or the condition holds for the settings.
+    pub fn applies(&self, settings: &Map<String, Value>) -> bool {
+        self.parsed_condition.as_ref().map_or(true, |condition| condition.is_true(settings))
+    }
+}
+
+impl TagConfig {
+    /// The paths of the input files, given the directory of the `readme.md`.
+    pub fn input_paths(&self, dir: impl AsRef<Path>) -> Vec<PathBuf> {
+        self.input_files.iter().map(|file| dir.as_ref().join(file)).collect()
+    }
+
+    /// Reads and merges the input files, given the directory of the `readme.md`.
+    pub fn merge_input_files(&self, dir: impl AsRef<Path>) -> Result<Merged> {
+        merge_files(self.input_paths(dir))
+    }
+}
+
+/// Adds the settings that are not set yet, combining lists and mappings.
+fn merge_settings(target: &mut Map<String, Value>, source: &Map<String, Value>) {
+    for (name, value) in source {
+        match (target.get_mut(name), value) {
+            (None, value) => {
+                target.insert(name.clone(), value.clone());
+            }
+            (Some(Value::Array(existing)), Value::Array(values)) => {
+                for value in values {
+                    if !existing.contains(value) {
+                        existing.push(value.clone());
+                    }
+                }
+            }
+            (Some(Value::Object(existing)), Value::Object(values)) => merge_settings(existing, values),
+            (Some(_), _) => {}
+        }
+    }
+}
+
+/// A block condition, such as `$(tag) == 'package-2020-03' || $(python)`.
+#[derive(Clone, Debug, PartialEq)]
+enum Condition {
+    Setting(String),
+    Literal(String),
+    Equals(Box<Condition>, Box<Condition>),
+    NotEquals(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Setting(String),
+    Literal(String),
+    Equals,
+    NotEquals,
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+impl Condition {
+    fn parse(text: &str) -> Option<Self> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let condition = parser.or()?;
+        if parser.position == tokens.len() {
+            Some(condition)
+        } else {
+            None
+        }
+    }
+
+    fn value(&self, settings: &Map<String, Value>) -> Option<String> {
+        match self {
+            Condition::Setting(name) => match settings.get(name)? {
+                Value::String(value) => Some(value.clone()),
+                Value::Null => None,
+                value => Some(value.to_string()),
+            },
+            Condition::Literal(value) => Some(value.clone()),
+            _ => Some(self.is_true(settings).to_string()),
+        }
+    }
+
+    fn is_true(&self, settings: &Map<String, Value>) -> bool {
+        match self {
+            Condition::Setting(name) => match settings.get(name) {
+                None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+                Some(Value::String(value)) => !value.is_empty(),
+                Some(_) => true,
+            },
+            Condition::Literal(value) => !value.is_empty() && value != "false",
+            Condition::Equals(left, right) => left.value(settings) == right.value(settings),
+            Condition::NotEquals(left, right) => left.value(settings) != right.value(settings),
+            Condition::Not(condition) => !condition.is_true(settings),
+            Condition::And(left, right) => left.is_true(settings) && right.is_true(settings),
+            Condition::Or(left, right) => left.is_true(settings) || right.is_true(settings),
+        }
+    }
+
+    fn tags(&self, tags: &mut Vec<String>) {
+        match self {
+            Condition::Equals(left, right) | Condition::NotEquals(left, right) => {
+                if let (Condition::Setting(name), Condition::Literal(tag)) | (Condition::Literal(tag), Condition::Setting(name)) =
+                    (left.as_ref(), right.as_ref())
+                {
+                    if name == "tag" && !tags.contains(tag) {
+                        tags.push(tag.clone());
+                    }
+                }
+            }
+            Condition::Not(condition) => condition.tags(tags),
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.tags(tags);
+                right.tags(tags);
+            }
+            Condition::Setting(_) | Condition::Literal(_) => {}
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let (token, length) = if let Some(name) = rest.strip_prefix("$(") {
+            let end = name.find(')')?;
+            (Token::Setting(name[..end].trim().to_owned()), end + 3)
+        } else if rest.starts_with('\'') || rest.starts_with('"') {
+            let quote = rest.chars().next()?;
+            let end = rest[1..].find(quote)?;
+            (Token::Literal(rest[1..end + 1].to_owned()), end + 2)
+        } else if rest.starts_with("==") {
+            (Token::Equals, 2)
+        } else if rest.starts_with("!=") {
+            (Token::NotEquals, 2)
+        } else if rest.starts_with("&&") {
+            (Token::And, 2)
+        } else if rest.starts_with("||") {
+            (Token::Or, 2)
+        } else if rest.starts_with('!') {
+            (Token::Not, 1)
+        } else if rest.starts_with('(') {
+            (Token::Open, 1)
+        } else if rest.starts_with(')') {
+            (Token::Close, 1)
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return None;
+            }
+            (Token::Literal(rest[..end].to_owned()), end)
+        };
+        tokens.push(token);
+        rest = rest[length..].trim_start();
+    }
+    Some(tokens)
+}
+
+/// A recursive descent parser where `||` binds looser than `&&`, which binds looser than `==` and `!=`.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn next_if(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.position) == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Option<Condition> {
+        let mut condition = self.and()?;
+        while self.next_if(&Token::Or) {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Some(condition)
+    }
+
+    fn and(&mut self) -> Option<Condition> {
+        let mut condition = self.unary()?;
+        while self.next_if(&Token::And) {
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Some(condition)
+    }
+
+    fn unary(&mut self) -> Option<Condition> {
+        if self.next_if(&Token::Not) {
+            return Some(Condition::Not(Box::new(self.unary()?)));
+        }
+        if self.next_if(&Token::Open) {
+            let condition = self.or()?;
+            return if self.next_if(&Token::Close) { Some(condition) } else { None };
+        }
+        let left = self.operand()?;
+        if self.next_if(&Token::Equals) {
+            Some(Condition::Equals(Box::new(left), Box::new(self.operand()?)))
+        } else if self.next_if(&Token::NotEquals) {
+            Some(Condition::NotEquals(Box::new(left), Box::new(self.operand()?)))
+        } else {
+            Some(left)
+        }
+    }
+
+    fn operand(&mut self) -> Option<Condition> {
+        let condition = match self.tokens.get(self.position)? {
+            Token::Setting(name) => Condition::Setting(name.clone()),
+            Token::Literal(value) => Condition::Literal(value.clone()),
+            _ => return None,
+        };
+        self.position += 1;
+        Some(condition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const README: &str = r#"# VMware
+
+``` yaml
+openapi-type: arm
+tag: package-2020-03
+directive:
+  - suppress: R4009
+```
+
+### Tag: package-2020-03
+
+```yaml $(tag) == 'package-2020-03'
+input-file:
+- Microsoft.AVS/stable/2020-03-20/vmware.json
+```
+
+### Tag: package-2019-08-09-preview
+
+```yaml $(tag) == 'package-2019-08-09-preview' || $(tag) == "package-2019-08"
+input-file:
+  - Microsoft.AVS/preview/2019-08-09-preview/vmware.json
+directive:
+  - where: $.definitions.Cloud
+    transform: $.description = "cloud"
+```
+
+```yaml !$(python) && ($(go) || $(java))
+clear-output-folder: true
+```
+
+``` bash
+autorest --tag=package-2020-03
+```
+"#;
+
+    #[test]
+    fn evaluates_blocks_for_a_tag() {
+        let readme = Readme::parse(README).unwrap();
+        assert_eq!(readme.blocks.len(), 4);
+        assert_eq!(readme.default_tag().as_deref(), Some("package-2020-03"));
+        assert_eq!(
+            readme.tags(),
+            vec!["package-2020-03", "package-2019-08-09-preview", "package-2019-08"]
+        );
+
+        let config = readme.tag("package-2019-08");
+        assert_eq!(config.input_files, vec!["Microsoft.AVS/preview/2019-08-09-preview/vmware.json"]);
+        assert_eq!(config.directives.len(), 2);
+        assert_eq!(config.settings["openapi-type"], "arm");
+        assert_eq!(readme.tag("package-2020-03").directives, vec![json!({ "suppress": "R4009" })]);
+
+        let mut args = Map::new();
+        args.insert("go".to_owned(), json!(true));
+        assert_eq!(readme.evaluate(&args).get("clear-output-folder"), Some(&json!(true)));
+        args.insert("python".to_owned(), json!(true));
+        assert_eq!(readme.evaluate(&args).get("clear-output-folder"), None);
+    }
+
+    #[test]
+    fn reports_invalid_conditions() {
+        let err = Readme::parse("text\n```yaml $(tag) == \n```\n").unwrap_err();
+        assert!(matches!(err, ReadmeError::Condition { line: 2, .. }));
+    }
+}
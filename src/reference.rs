@@ -1,9 +1,17 @@
 use crate::*;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{value::MapAccessDeserializer, DeserializeSeed, Error as _, IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value;
+use std::{borrow::Cow, fmt, marker::PhantomData, vec};
 
 /// https://swagger.io/docs/specification/using-ref/
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#referenceObject
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+/// Deserializing does not buffer the whole object like `#[serde(untagged)]` would. The fields that may be next to `$ref`
+/// and extensions are held back until `$ref` or another field is found, and the rest is read straight into `T`.
+/// An object with a `$ref` after other fields is still a reference.
+#[derive(Serialize, Clone, Debug, PartialEq)]
 #[serde(untagged)]
 pub enum ReferenceOr<T> {
     Reference {
@@ -29,6 +37,225 @@ pub enum ReferenceOr<T> {
     Item(T),
 }
 
+/// The fields that may be next to `$ref`.
+const SIBLINGS: &[&str] = &["title", "description", "type", "readOnly", "x-ms-client-flatten"];
+
+fn is_sibling(key: &str) -> bool {
+    SIBLINGS.contains(&key) || key.starts_with("x-")
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ReferenceOr<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(ReferenceOrVisitor(PhantomData))
+    }
+}
+
+struct ReferenceOrVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for ReferenceOrVisitor<T> {
+    type Value = ReferenceOr<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object with a $ref or an item")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut siblings: Vec<(Cow<'de, str>, Value)> = Vec::new();
+        loop {
+            let key = match map.next_key::<BorrowedKey>()? {
+                Some(key) => key.0,
+                None => return ItemAccess::new(siblings, None, map).deserialize(),
+            };
+            if key == "$ref" {
+                let reference: Reference = map.next_value().map_err(|err| with_field("$ref", err))?;
+                read_siblings(&mut map, &mut siblings)?;
+                return reference_with_siblings(reference, siblings);
+            } else if is_sibling(&key) {
+                siblings.push((key, map.next_value()?));
+            } else {
+                return ItemAccess::new(siblings, Some(key), map).deserialize();
+            }
+        }
+    }
+}
+
+/// Reads the rest of a map that has a `$ref`, keeping the fields that may be next to it.
+fn read_siblings<'de, A: MapAccess<'de>>(map: &mut A, siblings: &mut Vec<(Cow<'de, str>, Value)>) -> Result<(), A::Error> {
+    while let Some(key) = map.next_key::<BorrowedKey>()? {
+        if is_sibling(&key.0) {
+            siblings.push((key.0, map.next_value()?));
+        } else {
+            map.next_value::<IgnoredAny>()?;
+        }
+    }
+    Ok(())
+}
+
+fn reference_with_siblings<T, E: serde::de::Error>(reference: Reference, siblings: Vec<(Cow<str>, Value)>) -> Result<ReferenceOr<T>, E> {
+    let mut title = None;
+    let mut description = None;
+    let mut type_ = None;
+    let mut read_only = None;
+    let mut x_ms_client_flatten = None;
+    for (key, value) in siblings {
+        let parsed = match key.as_ref() {
+            "title" => serde_json::from_value(value).map(|value| title = value),
+            "description" => serde_json::from_value(value).map(|value| description = value),
+            "type" => serde_json::from_value(value).map(|value| type_ = value),
+            "readOnly" => serde_json::from_value(value).map(|value| read_only = value),
+            "x-ms-client-flatten" => serde_json::from_value(value).map(|value| x_ms_client_flatten = value),
+            _ => Ok(()),
+        };
+        parsed.map_err(|err| with_field(&key, err))?;
+    }
+    Ok(ReferenceOr::Reference {
+        reference,
+        title,
+        description,
+        type_,
+        read_only,
+        x_ms_client_flatten,
+    })
+}
+
+/// Gives `T` the fields held back, then the field that made it clear this is not a reference, then the rest of the map.
+/// A `$ref` found along the way still makes this a reference, as JSON objects are unordered.
+/// The fields that may be next to `$ref` are held on to in case that happens.
+struct ItemAccess<'de, A> {
+    held: vec::IntoIter<(Cow<'de, str>, Value)>,
+    next: Option<Cow<'de, str>>,
+    /// the map, unless it has ended
+    map: Option<A>,
+    /// the value to read next, if it was held back
+    value: Option<Value>,
+    /// every field that may be next to `$ref`
+    siblings: Vec<(Cow<'de, str>, Value)>,
+    /// the `$ref`, if one was found after the item was started
+    reference: Option<Reference>,
+    /// whether the value of the last key read from the map is still to be read
+    pending_value: bool,
+}
+
+impl<'de, A: MapAccess<'de>> ItemAccess<'de, A> {
+    fn new(held: Vec<(Cow<'de, str>, Value)>, next: Option<Cow<'de, str>>, map: A) -> Self {
+        let ended = next.is_none();
+        ItemAccess {
+            siblings: held.clone(),
+            held: held.into_iter(),
+            next,
+            map: if ended { None } else { Some(map) },
+            value: None,
+            reference: None,
+            pending_value: !ended,
+        }
+    }
+
+    fn deserialize<T: Deserialize<'de>>(mut self) -> Result<ReferenceOr<T>, A::Error> {
+        let item = T::deserialize(MapAccessDeserializer::new(&mut self));
+        if item.is_err() && self.reference.is_none() {
+            // the error may be from a field before a `$ref`, which makes the item irrelevant
+            if self.find_reference().is_err() {
+                return item.map(ReferenceOr::Item);
+            }
+        }
+        match self.reference.take() {
+            Some(reference) => reference_with_siblings(reference, self.siblings),
+            None => item.map(ReferenceOr::Item),
+        }
+    }
+
+    /// Reads the rest of the map after `T` failed, looking for a `$ref`.
+    fn find_reference(&mut self) -> Result<(), A::Error> {
+        if let Some(map) = &mut self.map {
+            if self.pending_value {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        self.read_rest()
+    }
+
+    /// Reads the rest of the map, keeping the `$ref` and the fields that may be next to it.
+    fn read_rest(&mut self) -> Result<(), A::Error> {
+        if let Some(mut map) = self.map.take() {
+            while let Some(key) = map.next_key::<BorrowedKey>()? {
+                if key.0 == "$ref" {
+                    self.reference = Some(map.next_value().map_err(|err| with_field("$ref", err))?);
+                } else if is_sibling(&key.0) {
+                    self.siblings.push((key.0, map.next_value()?));
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for ItemAccess<'de, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = if let Some((key, value)) = self.held.next() {
+            self.value = Some(value);
+            key
+        } else if let Some(key) = self.next.take() {
+            key
+        } else {
+            let map = match &mut self.map {
+                Some(map) => map,
+                None => return Ok(None),
+            };
+            let key = match map.next_key::<BorrowedKey>()? {
+                Some(key) => key.0,
+                None => {
+                    self.map = None;
+                    return Ok(None);
+                }
+            };
+            if key == "$ref" {
+                self.reference = Some(map.next_value().map_err(|err| with_field("$ref", err))?);
+                self.read_rest()?;
+                return Ok(None);
+            }
+            if is_sibling(&key) {
+                let value: Value = map.next_value()?;
+                self.siblings.push((key.clone(), value.clone()));
+                self.value = Some(value);
+            } else {
+                self.pending_value = true;
+            }
+            key
+        };
+        seed.deserialize(serde::de::value::StrDeserializer::new(&key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        // the types that read their fields through `ReadFields` name them in errors
+        match self.value.take() {
+            Some(value) => seed.deserialize(value).map_err(A::Error::custom),
+            None => match &mut self.map {
+                Some(map) => {
+                    self.pending_value = false;
+                    map.next_value_seed(seed)
+                }
+                None => Err(A::Error::custom("missing value")),
+            },
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Reference {
     pub file: Option<String>,
@@ -37,10 +264,27 @@ pub struct Reference {
 }
 
 impl Reference {
+    /// A reference to `#/{section}/{name}` within the same document.
+    pub fn local(section: &str, name: &str) -> Self {
+        Reference {
+            file: None,
+            path: vec![section.to_owned()],
+            name: Some(name.to_owned()),
+        }
+    }
+
     pub fn parse(str: &str) -> Result<Self, serde_json::Error> {
         let str = format!("\"{}\"", str);
         serde_json::from_str(&str)
     }
+
+    /// Returns the name if this is a reference to `#/{section}/{name}` within the same document.
+    pub fn local_name(&self, section: &str) -> Option<&str> {
+        match (&self.file, self.path.as_slice(), &self.name) {
+            (None, [path], Some(name)) if path == section => Some(name),
+            _ => None,
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for Reference {
@@ -91,11 +335,11 @@ impl Serialize for Reference {
     where
         S: Serializer,
     {
-        let mut str = self.file.clone().unwrap_or_else(String::new);
+        let mut str = self.file.clone().unwrap_or_default();
         let path = &self.path.join("/");
         if !path.is_empty() {
             str.push_str("#/");
-            str.push_str(&path);
+            str.push_str(path);
         }
         if let Some(name) = self.name.as_ref() {
             str.push('/');
@@ -109,7 +353,6 @@ impl Serialize for Reference {
 mod tests {
     use super::*;
     use crate::{Parameter, ReferenceOr};
-    use serde_json;
 
     #[test]
     fn can_parse_common_types() {
@@ -157,7 +400,7 @@ mod tests {
     fn deserializes() {
         let json = r#"{"$ref":"foo/bar"}"#;
         assert_eq!(
-            serde_json::from_str::<ReferenceOr<Parameter>>(&json).unwrap(),
+            serde_json::from_str::<ReferenceOr<Parameter>>(json).unwrap(),
             ReferenceOr::<Parameter>::Reference {
                 reference: Reference {
                     file: Some("foo/bar".into()),
@@ -193,4 +436,97 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn deserializes_siblings_before_ref() {
+        let json = r##"{"description":"the pet","x-ms-mutability":["read"],"$ref":"#/definitions/Pet","readOnly":true}"##;
+        match serde_json::from_str::<ReferenceOr<Schema>>(json).unwrap() {
+            ReferenceOr::Reference {
+                reference,
+                description,
+                read_only,
+                ..
+            } => {
+                assert_eq!(reference.local_name("definitions"), Some("Pet"));
+                assert_eq!(description.as_deref(), Some("the pet"));
+                assert_eq!(read_only, Some(true));
+            }
+            ReferenceOr::Item(_) => panic!("expected a reference"),
+        }
+    }
+
+    #[test]
+    fn deserializes_items_after_held_back_fields() {
+        let json = r#"{"description":"a pet","type":"object","required":["name"],"properties":{"name":{"type":"string"}}}"#;
+        match serde_json::from_str::<ReferenceOr<Schema>>(json).unwrap() {
+            ReferenceOr::Item(schema) => {
+                assert_eq!(schema.common.description.as_deref(), Some("a pet"));
+                assert_eq!(schema.required, vec!["name"]);
+                assert_eq!(schema.properties.len(), 1);
+            }
+            ReferenceOr::Reference { .. } => panic!("expected a schema"),
+        }
+    }
+
+    #[test]
+    fn names_the_failing_field() {
+        let json = r#"{"description":"a pet","name":"pet","in":"nowhere"}"#;
+        let err = serde_json::from_str::<ReferenceOr<Parameter>>(json).unwrap_err();
+        assert!(err.to_string().starts_with("in: unknown variant `nowhere`"), "{}", err);
+
+        let json = r##"{"readOnly":"yes","$ref":"#/definitions/Pet"}"##;
+        let err = serde_json::from_str::<ReferenceOr<Schema>>(json).unwrap_err();
+        assert!(err.to_string().starts_with("readOnly: invalid type"), "{}", err);
+    }
+
+    #[test]
+    fn names_nested_and_common_fields() {
+        for (json, expected) in [
+            (
+                r#"{"name":"a","in":"query","type":"strin"}"#,
+                "type: unknown variant `strin`, expected one of `string`, `number`, `integer`, `boolean`, `array`, `object`, `file` at line 1 column 40",
+            ),
+            (
+                r#"{"name":"a","in":"query","maxLength":"x"}"#,
+                "maxLength: invalid type: string \"x\", expected usize at line 1 column 41",
+            ),
+            (
+                r#"{"name":"a","in":"body","schema":{"properties":{"b":{"type":"strin"}}}}"#,
+                "schema: properties: b: type: unknown variant `strin`, expected one of `string`, `number`, `integer`, `boolean`, `array`, `object`, `file` at line 1 column 71",
+            ),
+            (
+                r#"{"name":"a","in":"body","schema":{"items":{"items":{"minItems":-1}}}}"#,
+                "schema: items: items: minItems: invalid value: integer `-1`, expected usize at line 1 column 69",
+            ),
+        ] {
+            let err = serde_json::from_str::<ReferenceOr<Parameter>>(json).unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn deserializes_ref_after_other_fields() {
+        for json in [
+            r##"{"format":"uri","$ref":"#/definitions/Pet"}"##,
+            r##"{"items":{"type":"string"},"$ref":"#/definitions/Pet"}"##,
+            r##"{"default":"x","$ref":"#/definitions/Pet"}"##,
+            r##"{"type":"object","properties":{},"description":"the pet","$ref":"#/definitions/Pet","x-ms-client-flatten":true}"##,
+            r##"{"maxLength":"long","$ref":"#/definitions/Pet"}"##,
+        ] {
+            match serde_json::from_str::<ReferenceOr<Schema>>(json).unwrap() {
+                ReferenceOr::Reference { reference, .. } => assert_eq!(reference.local_name("definitions"), Some("Pet"), "{}", json),
+                ReferenceOr::Item(_) => panic!("expected a reference: {}", json),
+            }
+        }
+        let json = r##"{"name":"pet","in":"body","description":"the pet","$ref":"#/parameters/Pet"}"##;
+        match serde_json::from_str::<ReferenceOr<Parameter>>(json).unwrap() {
+            ReferenceOr::Reference {
+                reference, description, ..
+            } => {
+                assert_eq!(reference.local_name("parameters"), Some("Pet"));
+                assert_eq!(description.as_deref(), Some("the pet"));
+            }
+            ReferenceOr::Item(_) => panic!("expected a reference"),
+        }
+    }
 }
@@ -0,0 +1,477 @@
+//! The graph of references between the definitions, parameters, responses and operations of a document
+//! Its strongly connected components are the recursive models, which generated code needs to box.
+
+use crate::*;
+use indexmap::{IndexMap, IndexSet};
+use serde_json::{json, Value};
+use std::fmt::{self, Write};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GraphNode {
+    Component(Component),
+    /// an operation, or a path item for the parameters shared by its operations, by JSON Pointer
+    Operation(String),
+    /// `x-ms-parameterized-host`
+    Host,
+    /// a reference to another file, as written
+    External(String),
+}
+
+impl GraphNode {
+    /// The JSON Pointer to the node, or the reference for an external node.
+    pub fn id(&self) -> String {
+        match self {
+            GraphNode::Component(component) => component.pointer(),
+            GraphNode::Operation(pointer) => pointer.clone(),
+            GraphNode::Host => "/x-ms-parameterized-host".to_owned(),
+            GraphNode::External(reference) => reference.clone(),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            GraphNode::Component(Component::Definition(_)) => "definition",
+            GraphNode::Component(Component::Parameter(_)) => "parameter",
+            GraphNode::Component(Component::Response(_)) => "response",
+            GraphNode::Operation(_) => "operation",
+            GraphNode::Host => "host",
+            GraphNode::External(_) => "external",
+        }
+    }
+}
+
+impl fmt::Display for GraphNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id())
+    }
+}
+
+/// Where a reference is found within the object that refers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    Property,
+    AllOf,
+    Items,
+    AdditionalProperties,
+    Parameter,
+    Response,
+    /// the `schema` of a body parameter or a response, or a definition that is only a reference
+    Schema,
+    /// an `x-ms-examples` file
+    Example,
+    /// a path item that is a reference
+    PathItem,
+}
+
+impl EdgeKind {
+    /// Reads the pointer from the root, so that names such as a property called `items` are not taken for keywords.
+    fn from_pointer(pointer: &str) -> Self {
+        let mut tokens = pointer.split('/').skip(1);
+        let mut kind = EdgeKind::Schema;
+        while let Some(token) = tokens.next() {
+            // the keywords followed by a name or index
+            let named = match token {
+                "properties" => Some(EdgeKind::Property),
+                "allOf" => Some(EdgeKind::AllOf),
+                "parameters" => Some(EdgeKind::Parameter),
+                "responses" => Some(EdgeKind::Response),
+                "x-ms-examples" => Some(EdgeKind::Example),
+                "paths" | "x-ms-paths" => Some(EdgeKind::PathItem),
+                "definitions" | "headers" => Some(EdgeKind::Schema),
+                _ => None,
+            };
+            kind = match named {
+                Some(kind) => {
+                    tokens.next();
+                    kind
+                }
+                None => match token {
+                    "items" => EdgeKind::Items,
+                    "additionalProperties" => EdgeKind::AdditionalProperties,
+                    _ => EdgeKind::Schema,
+                },
+            };
+        }
+        kind
+    }
+
+    /// Whether the target is held in an array or a map, which generated code stores on the heap.
+    pub fn is_collection(&self) -> bool {
+        matches!(self, EdgeKind::Items | EdgeKind::AdditionalProperties)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeKind::Property => "property",
+            EdgeKind::AllOf => "allOf",
+            EdgeKind::Items => "items",
+            EdgeKind::AdditionalProperties => "additionalProperties",
+            EdgeKind::Parameter => "parameter",
+            EdgeKind::Response => "response",
+            EdgeKind::Schema => "schema",
+            EdgeKind::Example => "example",
+            EdgeKind::PathItem => "pathItem",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphEdge {
+    pub from: GraphNode,
+    pub to: GraphNode,
+    pub kind: EdgeKind,
+    /// The JSON Pointer to the object holding the `$ref`.
+    pub pointer: String,
+}
+
+/// Every component and operation of a document, with an edge for every reference.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ReferenceGraph {
+    pub nodes: IndexSet<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// The node of the object containing the JSON Pointer.
+fn source_node(pointer: &str) -> Option<GraphNode> {
+    if let Some(component) = Component::from_pointer(pointer) {
+        return Some(GraphNode::Component(component));
+    }
+    let tokens: Vec<&str> = pointer.split('/').collect();
+    match tokens.as_slice() {
+        ["", "paths", path, method, ..] | ["", "x-ms-paths", path, method, ..] if METHODS.contains(method) => {
+            Some(GraphNode::Operation(format!("/{}/{}/{}", tokens[1], path, method)))
+        }
+        ["", "paths", path, ..] | ["", "x-ms-paths", path, ..] => Some(GraphNode::Operation(format!("/{}/{}", tokens[1], path))),
+        ["", "x-ms-parameterized-host", ..] => Some(GraphNode::Host),
+        _ => None,
+    }
+}
+
+impl OpenAPI {
+    /// Computes the references between the components and operations, including those to other files.
+    pub fn reference_graph(&self) -> ReferenceGraph {
+        let mut graph = ReferenceGraph::default();
+        graph.nodes.extend(
+            self.definitions
+                .keys()
+                .map(|name| GraphNode::Component(Component::Definition(name.clone()))),
+        );
+        graph.nodes.extend(
+            self.parameters
+                .keys()
+                .map(|name| GraphNode::Component(Component::Parameter(name.clone()))),
+        );
+        graph.nodes.extend(
+            self.responses
+                .keys()
+                .map(|status_code| GraphNode::Component(Component::Response(status_code.to_string()))),
+        );
+        graph
+            .nodes
+            .extend(self.operations().map(|operation| GraphNode::Operation(operation.pointer())));
+        self.walk_references(|pointer, reference| {
+            let to = match Component::from_reference(reference) {
+                Some(component) if reference.file.is_none() => GraphNode::Component(component),
+                _ if reference.file.is_some() => match serde_json::to_value(reference) {
+                    Ok(Value::String(reference)) => GraphNode::External(reference),
+                    _ => return,
+                },
+                _ => return,
+            };
+            let from = match source_node(pointer) {
+                Some(from) => from,
+                None => return,
+            };
+            graph.nodes.insert(from.clone());
+            graph.nodes.insert(to.clone());
+            graph.edges.push(GraphEdge {
+                from,
+                to,
+                kind: EdgeKind::from_pointer(pointer),
+                pointer: pointer.to_owned(),
+            });
+        });
+        graph
+    }
+}
+
+impl ReferenceGraph {
+    /// The graph in the DOT language of Graphviz, with the edges labeled by kind.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph references {\n");
+        for node in &self.nodes {
+            let _ = writeln!(dot, "  {} [shape={}];", dot_id(&node.id()), dot_shape(node));
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "  {} -> {} [label={}];",
+                dot_id(&edge.from.id()),
+                dot_id(&edge.to.id()),
+                dot_id(edge.kind.as_str())
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The graph as `{ "nodes": [{ "id", "kind" }], "edges": [{ "from", "to", "kind", "pointer" }] }`.
+    pub fn to_json(&self) -> Value {
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .map(|node| json!({ "id": node.id(), "kind": node.kind() }))
+            .collect();
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                json!({
+                    "from": edge.from.id(),
+                    "to": edge.to.id(),
+                    "kind": edge.kind.as_str(),
+                    "pointer": edge.pointer,
+                })
+            })
+            .collect();
+        json!({ "nodes": nodes, "edges": edges })
+    }
+
+    /// The strongly connected components, each listed before any component that refers to it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&GraphNode>> {
+        self.components(|_| true)
+    }
+
+    /// The strongly connected components with a cycle, which are the recursive models.
+    /// This includes cycles through arrays and maps; see `boxed_cycles` for the models that need a `Box`.
+    pub fn cycles(&self) -> Vec<Vec<&GraphNode>> {
+        self.cycles_following(|_| true)
+    }
+
+    /// The recursive models whose cycles do not pass through an array or a map,
+    /// so that one of the references must be boxed for the generated types to have a size.
+    pub fn boxed_cycles(&self) -> Vec<Vec<&GraphNode>> {
+        self.cycles_following(|edge| !edge.kind.is_collection())
+    }
+
+    fn cycles_following(&self, follow: impl Fn(&GraphEdge) -> bool) -> Vec<Vec<&GraphNode>> {
+        self.components(&follow)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || self
+                        .edges
+                        .iter()
+                        .any(|edge| follow(edge) && edge.from == *component[0] && edge.to == *component[0])
+            })
+            .collect()
+    }
+
+    fn components(&self, follow: impl Fn(&GraphEdge) -> bool) -> Vec<Vec<&GraphNode>> {
+        let mut successors: IndexMap<usize, Vec<usize>> = IndexMap::new();
+        for edge in self.edges.iter().filter(|edge| follow(edge)) {
+            if let (Some(from), Some(to)) = (self.nodes.get_index_of(&edge.from), self.nodes.get_index_of(&edge.to)) {
+                successors.entry(from).or_default().push(to);
+            }
+        }
+        let components = tarjan(self.nodes.len(), |node| {
+            successors.get(&node).map(Vec::as_slice).unwrap_or_default()
+        });
+        components
+            .into_iter()
+            .map(|component| component.into_iter().filter_map(|index| self.nodes.get_index(index)).collect())
+            .collect()
+    }
+}
+
+fn dot_id(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn dot_shape(node: &GraphNode) -> &'static str {
+    match node {
+        GraphNode::Component(Component::Definition(_)) => "box",
+        GraphNode::Component(_) => "ellipse",
+        GraphNode::Operation(_) | GraphNode::Host => "diamond",
+        GraphNode::External(_) => "note",
+    }
+}
+
+/// Tarjan's algorithm, without recursion so that long chains of references do not overflow the stack.
+fn tarjan<'a>(len: usize, successors: impl Fn(usize) -> &'a [usize]) -> Vec<Vec<usize>> {
+    let mut index = vec![None; len];
+    let mut low_link = vec![0; len];
+    let mut on_stack = vec![false; len];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+    for root in 0..len {
+        if index[root].is_some() {
+            continue;
+        }
+        // each frame is a node and how many of its successors have been visited
+        let mut frames = vec![(root, 0)];
+        index[root] = Some(next_index);
+        low_link[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some(&mut (node, ref mut visited)) = frames.last_mut() {
+            if let Some(&successor) = successors(node).get(*visited) {
+                *visited += 1;
+                match index[successor] {
+                    None => {
+                        index[successor] = Some(next_index);
+                        low_link[successor] = next_index;
+                        next_index += 1;
+                        stack.push(successor);
+                        on_stack[successor] = true;
+                        frames.push((successor, 0));
+                    }
+                    Some(successor_index) if on_stack[successor] => low_link[node] = low_link[node].min(successor_index),
+                    Some(_) => {}
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low_link[parent] = low_link[parent].min(low_link[node]);
+            }
+            if Some(low_link[node]) == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.reverse();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_edges_by_pointer() {
+        for (pointer, kind) in [
+            ("/definitions/X/properties/items", EdgeKind::Property),
+            ("/definitions/X/properties/additionalProperties", EdgeKind::Property),
+            ("/definitions/X/properties/properties/items", EdgeKind::Items),
+            ("/definitions/items", EdgeKind::Schema),
+            ("/definitions/X/additionalProperties", EdgeKind::AdditionalProperties),
+            ("/definitions/X/allOf/0", EdgeKind::AllOf),
+            ("/parameters/Body/schema", EdgeKind::Schema),
+            ("/paths/~1pets/get/parameters/0", EdgeKind::Parameter),
+            ("/paths/~1pets/get/responses/200/schema/items", EdgeKind::Items),
+            ("/paths/~1pets/get/responses/200/headers/items", EdgeKind::Schema),
+            ("/paths/~1pets/get/x-ms-examples/items", EdgeKind::Example),
+            ("/paths/~1items", EdgeKind::PathItem),
+        ] {
+            assert_eq!(EdgeKind::from_pointer(pointer), kind, "{}", pointer);
+        }
+    }
+
+    #[test]
+    fn finds_recursive_models() {
+        let spec: OpenAPI = serde_json::from_str(
+            r##"{
+                "swagger": "2.0",
+                "info": { "title": "Trees", "version": "1" },
+                "paths": {
+                    "/trees/{name}": {
+                        "get": {
+                            "operationId": "Trees_Get",
+                            "responses": { "200": { "description": "OK", "schema": { "$ref": "#/definitions/Tree" } } },
+                            "x-ms-examples": { "Get": { "$ref": "./examples/Trees_Get.json" } }
+                        }
+                    }
+                },
+                "definitions": {
+                    "Tree": {
+                        "allOf": [{ "$ref": "#/definitions/Resource" }],
+                        "properties": {
+                            "root": { "$ref": "#/definitions/Node" },
+                            "tags": { "type": "object", "additionalProperties": { "$ref": "common.json#/definitions/Tag" } }
+                        }
+                    },
+                    "Node": { "properties": { "children": { "type": "array", "items": { "$ref": "#/definitions/Node" } } } },
+                    "Resource": { "properties": { "owner": { "$ref": "#/definitions/Owner" } } },
+                    "Owner": { "properties": { "resource": { "$ref": "#/definitions/Resource" } } }
+                }
+            }"##,
+        )
+        .unwrap();
+        let graph = spec.reference_graph();
+        let edges: Vec<(String, String, EdgeKind)> = graph.edges.iter().map(|edge| (edge.from.id(), edge.to.id(), edge.kind)).collect();
+        assert_eq!(
+            edges,
+            [
+                (
+                    "/paths/~1trees~1{name}/get".to_owned(),
+                    "/definitions/Tree".to_owned(),
+                    EdgeKind::Schema
+                ),
+                (
+                    "/paths/~1trees~1{name}/get".to_owned(),
+                    "./examples/Trees_Get.json".to_owned(),
+                    EdgeKind::Example
+                ),
+                ("/definitions/Tree".to_owned(), "/definitions/Node".to_owned(), EdgeKind::Property),
+                (
+                    "/definitions/Tree".to_owned(),
+                    "common.json#/definitions/Tag".to_owned(),
+                    EdgeKind::AdditionalProperties
+                ),
+                ("/definitions/Tree".to_owned(), "/definitions/Resource".to_owned(), EdgeKind::AllOf),
+                ("/definitions/Node".to_owned(), "/definitions/Node".to_owned(), EdgeKind::Items),
+                (
+                    "/definitions/Resource".to_owned(),
+                    "/definitions/Owner".to_owned(),
+                    EdgeKind::Property
+                ),
+                (
+                    "/definitions/Owner".to_owned(),
+                    "/definitions/Resource".to_owned(),
+                    EdgeKind::Property
+                ),
+            ]
+        );
+
+        let cycles: Vec<Vec<String>> = graph
+            .cycles()
+            .into_iter()
+            .map(|cycle| cycle.into_iter().map(GraphNode::id).collect())
+            .collect();
+        assert_eq!(
+            cycles,
+            [
+                vec!["/definitions/Node".to_owned()],
+                vec!["/definitions/Resource".to_owned(), "/definitions/Owner".to_owned()]
+            ]
+        );
+        let boxed: Vec<Vec<String>> = graph
+            .boxed_cycles()
+            .into_iter()
+            .map(|cycle| cycle.into_iter().map(GraphNode::id).collect())
+            .collect();
+        assert_eq!(boxed, [vec!["/definitions/Resource".to_owned(), "/definitions/Owner".to_owned()]]);
+        let components = graph.strongly_connected_components();
+        let position = |id: &str| components.iter().position(|component| component.iter().any(|node| node.id() == id));
+        assert!(position("/definitions/Node") < position("/definitions/Tree"));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("  \"/definitions/Node\" -> \"/definitions/Node\" [label=\"items\"];\n"));
+        let json = graph.to_json();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 7);
+        assert_eq!(json["edges"][3]["kind"], "additionalProperties");
+    }
+}
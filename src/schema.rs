@@ -1,26 +1,25 @@
 use crate::*;
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::fmt;
 
 // http://json.schemastore.org/swagger-2.0
 
 /// The transfer protocol of the API. Values MUST be from the list: "http", "https", "ws", "wss".
 /// If the schemes is not included, the default scheme to be used is the one used to access the Swagger definition itself.
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Scheme {
+    #[default]
     Http,
     Https,
     Ws,
     Wss,
 }
 
-impl Default for Scheme {
-    fn default() -> Self {
-        Scheme::Http
-    }
-}
-
 /// https://swagger.io/docs/specification/data-models/data-types/
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#data-types
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -40,7 +39,7 @@ pub type SecurityRequirement = IndexMap<String, Vec<String>>;
 
 /// https://swagger.io/docs/specification/2-0/describing-responses/
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#responseObject
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, PartialEq, Default)]
 pub struct Response {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
@@ -48,13 +47,17 @@ pub struct Response {
     pub schema: Option<ReferenceOr<Schema>>,
     #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
     pub headers: IndexMap<String, ReferenceOr<Header>>,
+    /// example response bodies by MIME type
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub examples: IndexMap<String, serde_json::Value>,
 
     #[serde(rename = "x-ms-error-response", skip_serializing_if = "Option::is_none")]
     pub x_ms_error_response: Option<bool>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum AdditionalProperties {
     Boolean(bool),
     Schema(ReferenceOr<Schema>),
@@ -63,7 +66,7 @@ pub enum AdditionalProperties {
 /// common fields in both Schema Object & Parameter Object
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#schemaObject
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#parameter-object
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaCommon {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -128,7 +131,8 @@ pub struct SchemaCommon {
 }
 
 /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#schemaObject
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+/// Deserializing reads the fields of `common` along with the others, without buffering the object.
+#[derive(Clone, Debug, Serialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Schema {
     #[serde(flatten)]
@@ -155,6 +159,9 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub read_only: Option<bool>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xml: Option<Xml>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_docs: Option<ExternalDocumentation>,
 
@@ -185,3 +192,112 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<serde_json::Value>,
 }
+
+impl<'de> ReadFields<'de> for Response {
+    fn read_field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "description" => self.description = next_value(map, key)?,
+            "schema" => self.schema = next_value(map, key)?,
+            "headers" => self.headers = next_value::<NamedMap<_>, _>(map, key)?.0,
+            "examples" => self.examples = next_value(map, key)?,
+            "x-ms-error-response" => self.x_ms_error_response = next_value(map, key)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+impl<'de> Deserialize<'de> for Response {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FieldsVisitor::new("a response"))
+    }
+}
+
+impl<'de> ReadFields<'de> for SchemaCommon {
+    fn read_field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "description" => self.description = next_value(map, key)?,
+            "type" => self.type_ = next_value(map, key)?,
+            "format" => self.format = next_value(map, key)?,
+            "items" => self.items = next_value(map, key)?,
+            "default" => self.default = next_value(map, key)?,
+            "maximum" => self.maximum = next_value(map, key)?,
+            "exclusiveMaximum" => self.exclusive_maximum = next_value(map, key)?,
+            "minimum" => self.minimum = next_value(map, key)?,
+            "exclusiveMinimum" => self.exclusive_minimum = next_value(map, key)?,
+            "maxLength" => self.max_length = next_value(map, key)?,
+            "minLength" => self.min_length = next_value(map, key)?,
+            "pattern" => self.pattern = next_value(map, key)?,
+            "maxItems" => self.max_items = next_value(map, key)?,
+            "minItems" => self.min_items = next_value(map, key)?,
+            "uniqueItems" => self.unique_items = next_value(map, key)?,
+            "enum" => self.enum_ = next_value(map, key)?,
+            "multipleOf" => self.multiple_of = next_value(map, key)?,
+            "x-ms-enum" => self.x_ms_enum = next_value(map, key)?,
+            "x-ms-client-name" => self.x_ms_client_name = next_value(map, key)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemaCommon {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FieldsVisitor::new("a schema"))
+    }
+}
+
+impl<'de> ReadFields<'de> for Schema {
+    fn read_field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<bool, A::Error> {
+        match key {
+            "title" => self.title = next_value(map, key)?,
+            "required" => self.required = next_value(map, key)?,
+            "properties" => self.properties = next_value::<NamedMap<_>, _>(map, key)?.0,
+            "additionalProperties" => self.additional_properties = next_value(map, key)?,
+            "allOf" => self.all_of = next_value(map, key)?,
+            "discriminator" => self.discriminator = next_value(map, key)?,
+            "readOnly" => self.read_only = next_value(map, key)?,
+            "xml" => self.xml = next_value(map, key)?,
+            "externalDocs" => self.external_docs = next_value(map, key)?,
+            "x-ms-secret" => self.x_ms_secret = next_value(map, key)?,
+            "x-ms-azure-resource" => self.x_ms_azure_resource = next_value(map, key)?,
+            "x-ms-mutability" => self.x_ms_mutability = next_value(map, key)?,
+            "x-ms-external" => self.x_ms_external = next_value(map, key)?,
+            "x-nullable" => self.x_nullable = next_value(map, key)?,
+            "x-ms-discriminator-value" => self.x_ms_discriminator_value = next_value(map, key)?,
+            "example" => self.example = next_value(map, key)?,
+            _ => return self.common.read_field(key, map),
+        }
+        Ok(true)
+    }
+}
+
+impl<'de> Deserialize<'de> for Schema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(FieldsVisitor::new("a schema"))
+    }
+}
+
+impl<'de> Deserialize<'de> for AdditionalProperties {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AdditionalPropertiesVisitor)
+    }
+}
+
+struct AdditionalPropertiesVisitor;
+
+impl<'de> Visitor<'de> for AdditionalPropertiesVisitor {
+    type Value = AdditionalProperties;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean or a schema")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(AdditionalProperties::Boolean(value))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        ReferenceOr::deserialize(MapAccessDeserializer::new(map)).map(AdditionalProperties::Schema)
+    }
+}
@@ -45,13 +45,12 @@ pub enum Flow {
 mod tests {
     use super::*;
     use indexmap::IndexMap;
-    use serde_json;
 
     #[test]
     fn api_deserializes() {
         let json = r#"{"type":"apiKey", "name":"foo", "in": "query"}"#;
         assert_eq!(
-            serde_json::from_str::<Security>(&json).unwrap(),
+            serde_json::from_str::<Security>(json).unwrap(),
             Security::ApiKey {
                 name: "foo".into(),
                 in_: "query".into(),
@@ -78,7 +77,7 @@ mod tests {
     fn basic_deserializes() {
         let json = r#"{"type":"basic"}"#;
         assert_eq!(
-            serde_json::from_str::<Security>(&json).unwrap(),
+            serde_json::from_str::<Security>(json).unwrap(),
             Security::Basic { description: None }
         );
     }
@@ -95,12 +94,12 @@ mod tests {
         let mut scopes = IndexMap::new();
         scopes.insert("foo".into(), "bar".into());
         assert_eq!(
-            serde_json::from_str::<Security>(&json).unwrap(),
+            serde_json::from_str::<Security>(json).unwrap(),
             Security::Oauth2 {
                 flow: Flow::Implicit,
                 authorization_url: "foo/bar".into(),
                 token_url: None,
-                scopes: scopes,
+                scopes,
                 description: None,
             }
         );
@@ -117,7 +116,7 @@ mod tests {
                 flow: Flow::Implicit,
                 authorization_url: "foo/bar".into(),
                 token_url: None,
-                scopes: scopes,
+                scopes,
                 description: None,
             })
             .unwrap()
@@ -1,7 +1,7 @@
 // https://swagger.io/docs/specification/describing-responses/
 // https://swagger.io/docs/specification/2-0/describing-responses/
 // https://github.com/glademiller/openapiv3/blob/master/src/status_code.rs
-// but with Default without Range which is for v3
+// but with Default, and with Range from v3 for specs that mix the two
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -9,13 +9,49 @@ use std::fmt;
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum StatusCode {
     Code(u16),
+    /// a range of codes by their first digit, such as `2XX`
+    Range(u16),
     Default,
 }
 
+impl StatusCode {
+    /// The first digit of the code or range.
+    pub fn class(&self) -> Option<u16> {
+        match self {
+            StatusCode::Code(code) => Some(code / 100),
+            StatusCode::Range(range) => Some(*range),
+            StatusCode::Default => None,
+        }
+    }
+
+    /// Whether this is a `2XX` code or range.
+    pub fn is_success(&self) -> bool {
+        self.class() == Some(2)
+    }
+
+    /// Whether this is a `4XX` or `5XX` code or range, or `default`.
+    pub fn is_error(&self) -> bool {
+        match self.class() {
+            Some(class) => class == 4 || class == 5,
+            None => true,
+        }
+    }
+
+    /// Whether an actual status code is covered.
+    pub fn matches(&self, code: u16) -> bool {
+        match self {
+            StatusCode::Code(n) => *n == code,
+            StatusCode::Range(range) => code / 100 == *range,
+            StatusCode::Default => true,
+        }
+    }
+}
+
 impl fmt::Display for StatusCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             StatusCode::Code(n) => write!(f, "{}", n),
+            StatusCode::Range(n) => write!(f, "{}XX", n),
             StatusCode::Default => write!(f, "default"),
         }
     }
@@ -41,7 +77,7 @@ impl<'de> Deserialize<'de> for StatusCode {
             where
                 E: de::Error,
             {
-                if value >= 100 && value < 1000 {
+                if (100..1000).contains(&value) {
                     Ok(StatusCode::Code(value as u16))
                 } else {
                     Err(E::invalid_value(Unexpected::Signed(value), &self))
@@ -52,7 +88,7 @@ impl<'de> Deserialize<'de> for StatusCode {
             where
                 E: de::Error,
             {
-                if value >= 100 && value < 1000 {
+                if (100..1000).contains(&value) {
                     Ok(StatusCode::Code(value as u16))
                 } else {
                     Err(E::invalid_value(Unexpected::Unsigned(value), &self))
@@ -71,10 +107,17 @@ impl<'de> Deserialize<'de> for StatusCode {
                     return Err(E::invalid_value(Unexpected::Str(value), &"length 3"));
                 }
 
+                if let Some(range) = value.strip_suffix("XX").or_else(|| value.strip_suffix("xx")) {
+                    return match range.parse::<u16>() {
+                        Ok(range) if (1..6).contains(&range) => Ok(StatusCode::Range(range)),
+                        _ => Err(E::invalid_value(Unexpected::Str(value), &self)),
+                    };
+                }
+
                 if let Ok(number) = value.parse::<i64>() {
-                    return self.visit_i64(number);
+                    self.visit_i64(number)
                 } else {
-                    return Err(E::invalid_value(Unexpected::Str(value), &"not i64"));
+                    Err(E::invalid_value(Unexpected::Str(value), &"not i64"))
                 }
             }
         }
@@ -114,6 +157,17 @@ mod tests {
         assert_eq!(StatusCode::Default, from_str("default").unwrap(),);
     }
 
+    #[test]
+    fn deserialize_range() {
+        assert_eq!(StatusCode::Range(2), from_str("2XX").unwrap());
+        assert_eq!(StatusCode::Range(4), from_str("4xx").unwrap());
+        assert_eq!(serde_yaml::to_string(&StatusCode::Range(5)).unwrap().trim(), "5XX");
+        assert!(StatusCode::Range(2).matches(204));
+        assert!(StatusCode::Range(5).is_error());
+        assert!(!StatusCode::Code(302).is_success());
+        assert!(!StatusCode::Code(302).is_error());
+    }
+
     #[test]
     #[should_panic = "invalid value"]
     fn deserialize_invalid_range() {
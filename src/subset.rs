@@ -0,0 +1,185 @@
+//! Extracts a smaller document with only some of the operations
+
+use crate::*;
+
+/// Selects operations by tag, `operationId` or path.
+/// An operation is selected when it matches any of the criteria.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Selection {
+    /// Operations with any of these tags.
+    pub tags: Vec<String>,
+    /// Operations with an `operationId` matching any of these patterns, where `*` matches any characters and `?` a single one.
+    pub operation_ids: Vec<String>,
+    /// Operations at a path starting with any of these prefixes.
+    pub path_prefixes: Vec<String>,
+}
+
+impl Selection {
+    pub fn matches(&self, path: &str, operation: &Operation) -> bool {
+        operation.tags.iter().any(|tag| self.tags.contains(tag))
+            || operation
+                .operation_id
+                .as_deref()
+                .is_some_and(|operation_id| self.operation_ids.iter().any(|pattern| glob_match(pattern, operation_id)))
+            || self.path_prefixes.iter().any(|prefix| path.starts_with(prefix.as_str()))
+    }
+}
+
+impl OpenAPI {
+    /// Returns a copy with only the selected operations, along with the definitions, parameters, responses,
+    /// security definitions and tags they use. Path items that are references are not kept.
+    pub fn subset(&self, selection: &Selection) -> OpenAPI {
+        let mut spec = self.clone();
+        for paths in [&mut spec.paths, &mut spec.x_ms_paths] {
+            paths.retain(|path, item| match item {
+                ReferenceOr::Item(item) => {
+                    let PathItem {
+                        get,
+                        post,
+                        put,
+                        patch,
+                        delete,
+                        options,
+                        head,
+                        ..
+                    } = item;
+                    for operation in [get, post, put, patch, delete, options, head] {
+                        if !matches!(operation, Some(operation) if selection.matches(path, operation)) {
+                            *operation = None;
+                        }
+                    }
+                    item.operations().next().is_some()
+                }
+                ReferenceOr::Reference { .. } => false,
+            });
+        }
+
+        let used = spec.used();
+        spec.definitions
+            .retain(|name, _| used.contains(&Component::Definition(name.clone())));
+        spec.parameters.retain(|name, _| used.contains(&Component::Parameter(name.clone())));
+        spec.responses
+            .retain(|status_code, _| used.contains(&Component::Response(status_code.to_string())));
+
+        let operations: Vec<&Operation> = spec.operations().map(|operation| operation.operation).collect();
+        let security_names: Vec<&String> = spec
+            .security
+            .iter()
+            .chain(operations.iter().flat_map(|operation| operation.security.iter()))
+            .flat_map(|requirement| requirement.keys())
+            .collect();
+        let security_definitions = spec
+            .security_definitions
+            .iter()
+            .filter(|(name, _)| security_names.contains(name))
+            .map(|(name, security)| (name.clone(), security.clone()))
+            .collect();
+        let tags = spec
+            .tags
+            .iter()
+            .filter(|tag| operations.iter().any(|operation| operation.tags.contains(&tag.name)))
+            .cloned()
+            .collect();
+        spec.security_definitions = security_definitions;
+        spec.tags = tags;
+        spec
+    }
+}
+
+/// Matches text against a pattern where `*` matches any characters and `?` a single one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // the position of the last `*` and the text position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn glob_matches() {
+        assert!(glob_match("Pool_*", "Pool_List"));
+        assert!(glob_match("*_List*", "Pool_ListUsageMetrics"));
+        assert!(glob_match("Job?_Get", "Jobs_Get"));
+        assert!(!glob_match("Pool_*", "Job_List"));
+        assert!(!glob_match("Pool_Get", "Pool_GetAll"));
+    }
+
+    #[test]
+    fn keeps_selected_operations_and_what_they_use() {
+        let spec: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": {
+                "/pools": {
+                    "get": {
+                        "operationId": "Pool_List",
+                        "tags": ["Pools"],
+                        "security": [{ "aad": [] }],
+                        "responses": { "200": { "schema": { "$ref": "#/definitions/Pool" } } }
+                    },
+                    "post": {
+                        "operationId": "Pool_Add",
+                        "parameters": [{ "$ref": "#/parameters/PoolParameter" }],
+                        "responses": { "201": {} }
+                    }
+                },
+                "/jobs": {
+                    "get": {
+                        "operationId": "Job_List",
+                        "tags": ["Jobs"],
+                        "security": [{ "key": [] }],
+                        "responses": { "200": { "schema": { "$ref": "#/definitions/Job" } } }
+                    }
+                }
+            },
+            "definitions": { "Pool": { "type": "object" }, "Job": { "type": "object" } },
+            "parameters": { "PoolParameter": { "name": "pool", "in": "body", "schema": { "$ref": "#/definitions/Pool" } } },
+            "securityDefinitions": { "aad": { "type": "basic" }, "key": { "type": "basic" } },
+            "tags": [{ "name": "Pools" }, { "name": "Jobs" }]
+        }))
+        .unwrap();
+        let subset = spec.subset(&Selection {
+            operation_ids: vec!["Pool_L*".to_owned()],
+            ..Selection::default()
+        });
+        assert_eq!(subset.operations().count(), 1);
+        assert_eq!(subset.definitions.keys().collect::<Vec<_>>(), vec!["Pool"]);
+        assert!(subset.parameters.is_empty());
+        assert_eq!(subset.security_definitions.keys().collect::<Vec<_>>(), vec!["aad"]);
+        assert_eq!(subset.tags.len(), 1);
+
+        let subset = spec.subset(&Selection {
+            path_prefixes: vec!["/pools".to_owned()],
+            ..Selection::default()
+        });
+        assert_eq!(subset.operations().count(), 2);
+        assert_eq!(subset.parameters.keys().collect::<Vec<_>>(), vec!["PoolParameter"]);
+        assert!(!subset.paths.contains_key("/jobs"));
+    }
+}
@@ -0,0 +1,288 @@
+//! Language neutral types of schemas and parameters, along with how they map to Rust
+//! These are what code generators such as autorust build their models from.
+
+use crate::*;
+use indexmap::IndexMap;
+
+/// The type of a schema or parameter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeRef {
+    /// a `string`, `number`, `integer`, `boolean` or `file`
+    Primitive {
+        data_type: DataType,
+        format: Option<Format>,
+    },
+    Array(Box<TypeRef>),
+    /// a `string` keyed map, from `additionalProperties`
+    Map(Box<TypeRef>),
+    /// a definition, by name
+    Model(String),
+    /// an object with properties, declared in place instead of as a definition
+    InlineModel,
+    /// a set of values of the `base` type
+    Enum {
+        /// the `x-ms-enum` name, or the definition name
+        name: Option<String>,
+        model_as_string: bool,
+        base: Box<TypeRef>,
+    },
+    /// a definition with a `discriminator`, standing in for any of its children
+    Polymorphic(String),
+    /// any JSON value
+    Any,
+}
+
+/// The type of a property or parameter, along with whether it must be present.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldType {
+    pub type_: TypeRef,
+    /// listed in `required`, or a required parameter
+    pub required: bool,
+    /// from `x-nullable`
+    pub nullable: bool,
+}
+
+impl FieldType {
+    /// Whether the value may be missing or `null`.
+    pub fn is_optional(&self) -> bool {
+        !self.required || self.nullable
+    }
+
+    /// The Rust type, wrapped in an `Option` when optional.
+    pub fn rust_type(&self) -> String {
+        if self.is_optional() {
+            format!("Option<{}>", self.type_.rust_type())
+        } else {
+            self.type_.rust_type()
+        }
+    }
+}
+
+impl OpenAPI {
+    /// The type of a schema. References to definitions are `Model`, `Enum` or `Polymorphic` by name,
+    /// except for definitions that only alias a primitive type, which are followed.
+    pub fn schema_type(&self, schema: &ReferenceOr<Schema>) -> TypeRef {
+        match schema {
+            ReferenceOr::Item(schema) => self.inline_schema_type(schema),
+            ReferenceOr::Reference { reference, .. } => {
+                let name = match &reference.name {
+                    Some(name) => name,
+                    None => return TypeRef::Any,
+                };
+                let definition = match reference.local_name("definitions").and_then(|name| self.definitions.get(name)) {
+                    Some(definition) => self.resolve_schema(definition),
+                    // an external definition
+                    None => return TypeRef::Model(name.clone()),
+                };
+                match definition {
+                    Some(definition) => self.definition_type(name, definition),
+                    None => TypeRef::Model(name.clone()),
+                }
+            }
+        }
+    }
+
+    fn definition_type(&self, name: &str, definition: &Schema) -> TypeRef {
+        if let Some(model) = definition.common.enum_model() {
+            return TypeRef::Enum {
+                name: Some(model.name.unwrap_or(name).to_owned()),
+                model_as_string: model.model_as_string,
+                base: Box::new(primitive_type(&definition.common).unwrap_or(TypeRef::Any)),
+            };
+        }
+        if definition.discriminator.is_some() {
+            return TypeRef::Polymorphic(name.to_owned());
+        }
+        if definition.properties.is_empty() && definition.all_of.is_empty() && definition.additional_properties.is_none() {
+            if let Some(primitive) = primitive_type(&definition.common) {
+                return primitive;
+            }
+        }
+        TypeRef::Model(name.to_owned())
+    }
+
+    fn inline_schema_type(&self, schema: &Schema) -> TypeRef {
+        if !schema.properties.is_empty() || !schema.all_of.is_empty() {
+            return TypeRef::InlineModel;
+        }
+        match schema.additional_properties.as_ref() {
+            Some(AdditionalProperties::Schema(values)) => return TypeRef::Map(Box::new(self.schema_type(values))),
+            Some(AdditionalProperties::Boolean(true)) => return TypeRef::Map(Box::new(TypeRef::Any)),
+            _ => {}
+        }
+        self.common_type(&schema.common)
+    }
+
+    /// The type from `type`, `format`, `items` and `enum`, as used by parameters and headers.
+    pub fn common_type(&self, common: &SchemaCommon) -> TypeRef {
+        if let Some(model) = common.enum_model() {
+            return TypeRef::Enum {
+                name: model.name.map(str::to_owned),
+                model_as_string: model.model_as_string,
+                base: Box::new(primitive_type(common).unwrap_or(TypeRef::Any)),
+            };
+        }
+        match common.type_ {
+            Some(DataType::Array) => TypeRef::Array(Box::new(match common.items.as_ref() {
+                Some(items) => self.schema_type(items),
+                None => TypeRef::Any,
+            })),
+            _ => primitive_type(common).unwrap_or(TypeRef::Any),
+        }
+    }
+
+    /// The types of the properties declared by the schema, not including those from `allOf`.
+    pub fn property_types<'a>(&self, schema: &'a Schema) -> IndexMap<&'a str, FieldType> {
+        schema
+            .properties
+            .iter()
+            .map(|(name, property)| {
+                let nullable = self.resolve_schema(property).and_then(|property| property.x_nullable);
+                let field = FieldType {
+                    type_: self.schema_type(property),
+                    required: schema.required.contains(name),
+                    nullable: nullable.unwrap_or(false),
+                };
+                (name.as_str(), field)
+            })
+            .collect()
+    }
+
+    /// The type of a parameter. Path parameters are always required.
+    pub fn parameter_type(&self, parameter: &Parameter) -> FieldType {
+        let required = parameter.required.unwrap_or(false) || parameter.in_() == ParameterType::Path;
+        match &parameter.kind {
+            ParameterKind::Body(body) => FieldType {
+                type_: self.schema_type(&body.schema),
+                required,
+                nullable: self
+                    .resolve_schema(&body.schema)
+                    .and_then(|schema| schema.x_nullable)
+                    .unwrap_or(false),
+            },
+            ParameterKind::Path(parameter)
+            | ParameterKind::Query(parameter)
+            | ParameterKind::Header(parameter)
+            | ParameterKind::FormData(parameter) => FieldType {
+                type_: self.common_type(&parameter.common),
+                required,
+                nullable: false,
+            },
+        }
+    }
+}
+
+fn primitive_type(common: &SchemaCommon) -> Option<TypeRef> {
+    match &common.type_ {
+        Some(DataType::Array) | Some(DataType::Object) => None,
+        Some(data_type) => Some(TypeRef::Primitive {
+            data_type: data_type.clone(),
+            format: common.parsed_format(),
+        }),
+        // enums without a type are usually strings
+        None if !common.enum_.is_empty() => Some(TypeRef::Primitive {
+            data_type: DataType::String,
+            format: common.parsed_format(),
+        }),
+        None => None,
+    }
+}
+
+impl TypeRef {
+    /// The Rust type, using `chrono` for dates and times and the definition names for models.
+    pub fn rust_type(&self) -> String {
+        match self {
+            TypeRef::Primitive { data_type, format } => rust_primitive(data_type, format.as_ref()).to_owned(),
+            TypeRef::Array(items) => format!("Vec<{}>", items.rust_type()),
+            TypeRef::Map(values) => format!("std::collections::HashMap<String, {}>", values.rust_type()),
+            TypeRef::Model(name) | TypeRef::Polymorphic(name) => name.clone(),
+            TypeRef::Enum { name: Some(name), .. } => name.clone(),
+            TypeRef::Enum { name: None, base, .. } => base.rust_type(),
+            TypeRef::InlineModel | TypeRef::Any => "serde_json::Value".to_owned(),
+        }
+    }
+}
+
+fn rust_primitive(data_type: &DataType, format: Option<&Format>) -> &'static str {
+    match (data_type, format) {
+        (DataType::Integer, Some(Format::Int32)) => "i32",
+        (DataType::Integer, _) => "i64",
+        (DataType::Number, Some(Format::Float)) => "f32",
+        (DataType::Number, _) => "f64",
+        (DataType::Boolean, _) => "bool",
+        (DataType::String, Some(Format::Date)) => "chrono::NaiveDate",
+        (DataType::String, Some(Format::DateTime)) | (DataType::String, Some(Format::DateTimeRfc1123)) => "chrono::DateTime<chrono::Utc>",
+        (DataType::String, Some(Format::Binary)) | (DataType::File, _) => "Vec<u8>",
+        (DataType::String, _) => "String",
+        (DataType::Array, _) | (DataType::Object, _) => "serde_json::Value",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_types_to_rust() {
+        let spec: OpenAPI = serde_json::from_str(
+            r##"{
+                "swagger": "2.0",
+                "info": { "title": "Pets", "version": "1" },
+                "paths": {},
+                "definitions": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["id", "kind", "born"],
+                        "properties": {
+                            "id": { "type": "integer", "format": "int64" },
+                            "kind": { "$ref": "#/definitions/Kind" },
+                            "born": { "type": "string", "format": "date-time", "x-nullable": true },
+                            "name": { "$ref": "#/definitions/Name" },
+                            "tags": { "type": "object", "additionalProperties": { "type": "string" } },
+                            "friends": { "type": "array", "items": { "$ref": "#/definitions/Pet" } },
+                            "shape": { "$ref": "#/definitions/Shape" },
+                            "extra": { "type": "object", "properties": { "a": { "type": "string" } } },
+                            "data": {}
+                        }
+                    },
+                    "Kind": { "type": "string", "enum": ["dog", "cat"], "x-ms-enum": { "name": "PetKind", "modelAsString": true } },
+                    "Name": { "type": "string" },
+                    "Shape": { "type": "object", "discriminator": "kind", "properties": { "kind": { "type": "string" } } }
+                }
+            }"##,
+        )
+        .unwrap();
+        let pet = spec.resolve_schema(&spec.definitions["Pet"]).unwrap();
+        let types: Vec<(&str, String)> = spec
+            .property_types(pet)
+            .into_iter()
+            .map(|(name, field)| (name, field.rust_type()))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ("id", "i64"),
+                ("kind", "PetKind"),
+                ("born", "Option<chrono::DateTime<chrono::Utc>>"),
+                ("name", "Option<String>"),
+                ("tags", "Option<std::collections::HashMap<String, String>>"),
+                ("friends", "Option<Vec<Pet>>"),
+                ("shape", "Option<Shape>"),
+                ("extra", "Option<serde_json::Value>"),
+                ("data", "Option<serde_json::Value>"),
+            ]
+            .map(|(name, rust)| (name, rust.to_owned()))
+        );
+        assert_eq!(
+            spec.schema_type(&Schema::reference("Shape")),
+            TypeRef::Polymorphic("Shape".to_owned())
+        );
+
+        let parameter = Parameter::path("petId").data_type(DataType::Integer).format("int32");
+        let field = spec.parameter_type(&parameter);
+        assert!(!field.is_optional());
+        assert_eq!(field.rust_type(), "i32");
+        let parameter = Parameter::query("ids", DataType::Array);
+        assert_eq!(spec.parameter_type(&parameter).rust_type(), "Option<Vec<serde_json::Value>>");
+    }
+}
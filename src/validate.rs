@@ -0,0 +1,168 @@
+//! Checks a document for problems that deserialization alone does not catch
+
+use crate::*;
+use std::{collections::HashSet, fmt};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// the `operationId` is used by more than one operation
+    DuplicateOperationId { operation_id: String, pointer: String },
+    /// a local reference points to something that does not exist
+    UnresolvedReference { reference: Reference, pointer: String },
+    /// the path template has a parameter that the operation does not declare
+    MissingPathParameter { name: String, pointer: String },
+    /// the `x-ms-pageable` extension can not be applied to the operation
+    Pageable(PageableError),
+    /// an `x-ms-enum` name is reused with different values
+    EnumConflict { name: String, pointer: String, existing: String },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::DuplicateOperationId { operation_id, pointer } => {
+                write!(f, "{}: duplicate operationId '{}'", pointer, operation_id)
+            }
+            ValidationError::UnresolvedReference { reference, pointer } => {
+                let reference = serde_json::to_string(reference).unwrap_or_default();
+                write!(f, "{}: unable to resolve {}", pointer, reference)
+            }
+            ValidationError::MissingPathParameter { name, pointer } => write!(f, "{}: path parameter '{}' is not declared", pointer, name),
+            ValidationError::Pageable(err) => write!(f, "{}", err),
+            ValidationError::EnumConflict { name, pointer, existing } => {
+                write!(f, "{}: x-ms-enum '{}' conflicts with {}", pointer, name, existing)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl OpenAPI {
+    /// Checks the document, returning every problem found.
+    /// References to other files are not checked.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        let mut operation_ids = HashSet::new();
+        for operation in self.operations() {
+            if let Some(operation_id) = &operation.operation.operation_id {
+                if !operation_ids.insert(operation_id) {
+                    errors.push(ValidationError::DuplicateOperationId {
+                        operation_id: operation_id.clone(),
+                        pointer: operation.pointer(),
+                    });
+                }
+            }
+        }
+
+        self.walk_references(|pointer, reference| {
+            if reference.file.is_none() && !self.has_local(reference) {
+                errors.push(ValidationError::UnresolvedReference {
+                    reference: reference.clone(),
+                    pointer: pointer.to_owned(),
+                });
+            }
+        });
+
+        for operation in self.operations() {
+            for name in path_template_parameters(operation.path) {
+                if !self.declares_path_parameter(&operation, name) {
+                    errors.push(ValidationError::MissingPathParameter {
+                        name: name.to_owned(),
+                        pointer: operation.pointer(),
+                    });
+                }
+            }
+        }
+
+        errors.extend(self.pageables().into_iter().filter_map(Result::err).map(ValidationError::Pageable));
+
+        for conflict in self.enum_registry().conflicts {
+            errors.push(ValidationError::EnumConflict {
+                name: conflict.name.to_owned(),
+                pointer: conflict.pointer,
+                existing: conflict.existing,
+            });
+        }
+        errors
+    }
+
+    /// Returns true if a local reference points to an existing definition, parameter or response.
+    fn has_local(&self, reference: &Reference) -> bool {
+        if let Some(name) = reference.local_name("definitions") {
+            self.definitions.contains_key(name)
+        } else if let Some(name) = reference.local_name("parameters") {
+            self.parameters.contains_key(name)
+        } else if let Some(name) = reference.local_name("responses") {
+            self.responses.keys().any(|status_code| status_code.to_string() == name)
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the operation or its path item declares the path parameter.
+    /// Parameters in other files are assumed to match.
+    fn declares_path_parameter(&self, operation: &PathOperation, name: &str) -> bool {
+        let paths = if operation.x_ms_path { &self.x_ms_paths } else { &self.paths };
+        let path_parameters = match paths.get(operation.path) {
+            Some(ReferenceOr::Item(item)) => item.parameters.as_slice(),
+            _ => &[],
+        };
+        operation
+            .operation
+            .parameters
+            .iter()
+            .chain(path_parameters)
+            .any(|parameter| match self.resolve_parameter(parameter) {
+                Some(parameter) => parameter.in_() == ParameterType::Path && parameter.name == name,
+                None => matches!(parameter, ReferenceOr::Reference { reference, .. } if reference.file.is_some()),
+            })
+    }
+}
+
+/// Returns the `{name}` parameters of a path template, ignoring any query string of an `x-ms-paths` key.
+fn path_template_parameters(path: &str) -> impl Iterator<Item = &str> {
+    let path = path.split('?').next().unwrap_or_default();
+    path.split('{').skip(1).filter_map(|s| s.split('}').next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_problems() {
+        let spec: OpenAPI = serde_json::from_value(json!({
+            "swagger": "2.0",
+            "info": {},
+            "paths": {
+                "/clouds/{name}": {
+                    "get": {
+                        "operationId": "Clouds_Get",
+                        "responses": { "200": { "schema": { "$ref": "#/definitions/Missing" } } }
+                    },
+                    "put": {
+                        "operationId": "Clouds_Get",
+                        "parameters": [{ "$ref": "#/parameters/Name" }],
+                        "responses": { "200": {} }
+                    }
+                }
+            },
+            "parameters": {
+                "Name": { "name": "name", "in": "path", "required": true, "type": "string" }
+            }
+        }))
+        .unwrap();
+        let errors: Vec<String> = spec.validate().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "/paths/~1clouds~1{name}/put: duplicate operationId 'Clouds_Get'",
+                "/paths/~1clouds~1{name}/get/responses/200/schema: unable to resolve \"#/definitions/Missing\"",
+                "/paths/~1clouds~1{name}/get: path parameter 'name' is not declared",
+            ]
+        );
+    }
+}
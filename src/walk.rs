@@ -0,0 +1,393 @@
+//! Walks the schemas and references of a document
+
+use crate::*;
+
+/// Escapes a reference token of a JSON Pointer.
+/// https://tools.ietf.org/html/rfc6901
+pub fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Unescapes a reference token of a JSON Pointer.
+pub fn unescape_pointer_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+impl OpenAPI {
+    /// Calls `f` with the JSON Pointer of every inline Schema Object, Parameter Object and Header Object in the document.
+    /// Nested schemas in `properties`, `items`, `additionalProperties` and `allOf` are included.
+    pub fn walk_schemas<'a, F>(&'a self, mut f: F)
+    where
+        F: FnMut(&str, &'a SchemaCommon),
+    {
+        let f = &mut f;
+        for (name, schema) in &self.definitions {
+            walk_schema(&format!("/definitions/{}", escape_pointer_token(name)), schema, f);
+        }
+        for (name, parameter) in &self.parameters {
+            walk_parameter(&format!("/parameters/{}", escape_pointer_token(name)), parameter, f);
+        }
+        for (status_code, response) in &self.responses {
+            if let ReferenceOr::Item(response) = response {
+                walk_response(&format!("/responses/{}", status_code), response, f);
+            }
+        }
+        for (key, paths) in &[("paths", &self.paths), ("x-ms-paths", &self.x_ms_paths)] {
+            for (path, item) in paths.iter() {
+                let item = match item {
+                    ReferenceOr::Item(item) => item,
+                    ReferenceOr::Reference { .. } => continue,
+                };
+                let pointer = format!("/{}/{}", key, escape_pointer_token(path));
+                walk_parameters(&pointer, &item.parameters, f);
+                for (method, operation) in item.methods() {
+                    let pointer = format!("{}/{}", pointer, method);
+                    walk_parameters(&pointer, &operation.parameters, f);
+                    for (status_code, response) in &operation.responses {
+                        walk_response(&format!("{}/responses/{}", pointer, status_code), response, f);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn walk_parameters<'a>(pointer: &str, parameters: &'a [ReferenceOr<Parameter>], f: &mut impl FnMut(&str, &'a SchemaCommon)) {
+    for (i, parameter) in parameters.iter().enumerate() {
+        if let ReferenceOr::Item(parameter) = parameter {
+            walk_parameter(&format!("{}/parameters/{}", pointer, i), parameter, f);
+        }
+    }
+}
+
+fn walk_parameter<'a>(pointer: &str, parameter: &'a Parameter, f: &mut impl FnMut(&str, &'a SchemaCommon)) {
+    if let Some(common) = parameter.common() {
+        walk_common(pointer, common, f);
+    }
+    if let Some(schema) = parameter.body_schema() {
+        walk_schema(&format!("{}/schema", pointer), schema, f);
+    }
+}
+
+fn walk_response<'a>(pointer: &str, response: &'a Response, f: &mut impl FnMut(&str, &'a SchemaCommon)) {
+    if let Some(schema) = &response.schema {
+        walk_schema(&format!("{}/schema", pointer), schema, f);
+    }
+    for (name, header) in &response.headers {
+        if let ReferenceOr::Item(header) = header {
+            walk_common(&format!("{}/headers/{}", pointer, escape_pointer_token(name)), &header.common, f);
+        }
+    }
+}
+
+fn walk_schema<'a>(pointer: &str, schema: &'a ReferenceOr<Schema>, f: &mut impl FnMut(&str, &'a SchemaCommon)) {
+    let schema = match schema {
+        ReferenceOr::Item(schema) => schema,
+        ReferenceOr::Reference { .. } => return,
+    };
+    walk_common(pointer, &schema.common, f);
+    for (name, property) in &schema.properties {
+        walk_schema(&format!("{}/properties/{}", pointer, escape_pointer_token(name)), property, f);
+    }
+    if let Some(AdditionalProperties::Schema(additional_properties)) = schema.additional_properties.as_ref() {
+        walk_schema(&format!("{}/additionalProperties", pointer), additional_properties, f);
+    }
+    for (i, parent) in schema.all_of.iter().enumerate() {
+        walk_schema(&format!("{}/allOf/{}", pointer, i), parent, f);
+    }
+}
+
+fn walk_common<'a>(pointer: &str, common: &'a SchemaCommon, f: &mut impl FnMut(&str, &'a SchemaCommon)) {
+    f(pointer, common);
+    if let Some(items) = common.items.as_ref() {
+        walk_schema(&format!("{}/items", pointer), items, f);
+    }
+}
+
+impl OpenAPI {
+    /// Calls `f` with every reference in the document and the JSON Pointer of the object holding the `$ref`.
+    pub fn walk_references<'a, F>(&'a self, mut f: F)
+    where
+        F: FnMut(&str, &'a Reference),
+    {
+        let f = &mut f;
+        for (key, paths) in &[("paths", &self.paths), ("x-ms-paths", &self.x_ms_paths)] {
+            for (path, item) in paths.iter() {
+                let pointer = format!("/{}/{}", key, escape_pointer_token(path));
+                let item = match item {
+                    ReferenceOr::Item(item) => item,
+                    ReferenceOr::Reference { reference, .. } => {
+                        f(&pointer, reference);
+                        continue;
+                    }
+                };
+                refs_parameters(&pointer, &item.parameters, f);
+                for (method, operation) in item.methods() {
+                    refs_operation(&format!("{}/{}", pointer, method), operation, f);
+                }
+            }
+        }
+        for (name, schema) in &self.definitions {
+            refs_schema(&format!("/definitions/{}", escape_pointer_token(name)), schema, f);
+        }
+        for (name, parameter) in &self.parameters {
+            refs_parameter(&format!("/parameters/{}", escape_pointer_token(name)), parameter, f);
+        }
+        for (status_code, response) in &self.responses {
+            let pointer = format!("/responses/{}", status_code);
+            match response {
+                ReferenceOr::Item(response) => refs_response(&pointer, response, f),
+                ReferenceOr::Reference { reference, .. } => f(&pointer, reference),
+            }
+        }
+        if let Some(host) = &self.x_ms_parameterized_host {
+            refs_parameters("/x-ms-parameterized-host", &host.parameters, f);
+        }
+    }
+
+    /// Returns every reference in the document, in the same order as `walk_references`.
+    pub fn references_mut(&mut self) -> Vec<&mut Reference> {
+        let mut refs = Vec::new();
+        for item in self.paths.values_mut().chain(self.x_ms_paths.values_mut()) {
+            let item = match item {
+                ReferenceOr::Item(item) => item,
+                ReferenceOr::Reference { reference, .. } => {
+                    refs.push(reference);
+                    continue;
+                }
+            };
+            let PathItem {
+                get,
+                post,
+                put,
+                patch,
+                delete,
+                options,
+                head,
+                parameters,
+            } = item;
+            refs_parameters_mut(parameters, &mut refs);
+            for operation in vec![get, post, put, patch, delete, options, head].into_iter().flatten() {
+                refs_operation_mut(operation, &mut refs);
+            }
+        }
+        for schema in self.definitions.values_mut() {
+            refs_schema_mut(schema, &mut refs);
+        }
+        for parameter in self.parameters.values_mut() {
+            refs_parameter_mut(parameter, &mut refs);
+        }
+        for response in self.responses.values_mut() {
+            match response {
+                ReferenceOr::Item(response) => refs_response_mut(response, &mut refs),
+                ReferenceOr::Reference { reference, .. } => refs.push(reference),
+            }
+        }
+        if let Some(host) = &mut self.x_ms_parameterized_host {
+            refs_parameters_mut(&mut host.parameters, &mut refs);
+        }
+        refs
+    }
+}
+
+fn refs_operation<'a>(pointer: &str, operation: &'a Operation, f: &mut impl FnMut(&str, &'a Reference)) {
+    refs_parameters(pointer, &operation.parameters, f);
+    for (status_code, response) in &operation.responses {
+        refs_response(&format!("{}/responses/{}", pointer, status_code), response, f);
+    }
+    for (name, example) in &operation.x_ms_examples {
+        if let ReferenceOr::Reference { reference, .. } = example {
+            f(&format!("{}/x-ms-examples/{}", pointer, escape_pointer_token(name)), reference);
+        }
+    }
+}
+
+fn refs_parameters<'a>(pointer: &str, parameters: &'a [ReferenceOr<Parameter>], f: &mut impl FnMut(&str, &'a Reference)) {
+    for (i, parameter) in parameters.iter().enumerate() {
+        let pointer = format!("{}/parameters/{}", pointer, i);
+        match parameter {
+            ReferenceOr::Item(parameter) => refs_parameter(&pointer, parameter, f),
+            ReferenceOr::Reference { reference, .. } => f(&pointer, reference),
+        }
+    }
+}
+
+fn refs_parameter<'a>(pointer: &str, parameter: &'a Parameter, f: &mut impl FnMut(&str, &'a Reference)) {
+    if let Some(common) = parameter.common() {
+        refs_common(pointer, common, f);
+    }
+    if let Some(schema) = parameter.body_schema() {
+        refs_schema(&format!("{}/schema", pointer), schema, f);
+    }
+}
+
+fn refs_response<'a>(pointer: &str, response: &'a Response, f: &mut impl FnMut(&str, &'a Reference)) {
+    if let Some(schema) = &response.schema {
+        refs_schema(&format!("{}/schema", pointer), schema, f);
+    }
+    for (name, header) in &response.headers {
+        let pointer = format!("{}/headers/{}", pointer, escape_pointer_token(name));
+        match header {
+            ReferenceOr::Item(header) => refs_common(&pointer, &header.common, f),
+            ReferenceOr::Reference { reference, .. } => f(&pointer, reference),
+        }
+    }
+}
+
+fn refs_schema<'a>(pointer: &str, schema: &'a ReferenceOr<Schema>, f: &mut impl FnMut(&str, &'a Reference)) {
+    let schema = match schema {
+        ReferenceOr::Item(schema) => schema,
+        ReferenceOr::Reference { reference, .. } => return f(pointer, reference),
+    };
+    refs_common(pointer, &schema.common, f);
+    for (name, property) in &schema.properties {
+        refs_schema(&format!("{}/properties/{}", pointer, escape_pointer_token(name)), property, f);
+    }
+    if let Some(AdditionalProperties::Schema(additional_properties)) = schema.additional_properties.as_ref() {
+        refs_schema(&format!("{}/additionalProperties", pointer), additional_properties, f);
+    }
+    for (i, parent) in schema.all_of.iter().enumerate() {
+        refs_schema(&format!("{}/allOf/{}", pointer, i), parent, f);
+    }
+}
+
+fn refs_common<'a>(pointer: &str, common: &'a SchemaCommon, f: &mut impl FnMut(&str, &'a Reference)) {
+    if let Some(items) = common.items.as_ref() {
+        refs_schema(&format!("{}/items", pointer), items, f);
+    }
+}
+
+fn refs_operation_mut<'a>(operation: &'a mut Operation, refs: &mut Vec<&'a mut Reference>) {
+    refs_parameters_mut(&mut operation.parameters, refs);
+    for response in operation.responses.values_mut() {
+        refs_response_mut(response, refs);
+    }
+    for example in operation.x_ms_examples.values_mut() {
+        if let ReferenceOr::Reference { reference, .. } = example {
+            refs.push(reference);
+        }
+    }
+}
+
+fn refs_parameters_mut<'a>(parameters: &'a mut [ReferenceOr<Parameter>], refs: &mut Vec<&'a mut Reference>) {
+    for parameter in parameters {
+        match parameter {
+            ReferenceOr::Item(parameter) => refs_parameter_mut(parameter, refs),
+            ReferenceOr::Reference { reference, .. } => refs.push(reference),
+        }
+    }
+}
+
+fn refs_parameter_mut<'a>(parameter: &'a mut Parameter, refs: &mut Vec<&'a mut Reference>) {
+    match &mut parameter.kind {
+        ParameterKind::Body(body) => refs_schema_mut(&mut body.schema, refs),
+        ParameterKind::Path(parameter)
+        | ParameterKind::Query(parameter)
+        | ParameterKind::Header(parameter)
+        | ParameterKind::FormData(parameter) => refs_common_mut(&mut parameter.common, refs),
+    }
+}
+
+fn refs_response_mut<'a>(response: &'a mut Response, refs: &mut Vec<&'a mut Reference>) {
+    if let Some(schema) = &mut response.schema {
+        refs_schema_mut(schema, refs);
+    }
+    for header in response.headers.values_mut() {
+        match header {
+            ReferenceOr::Item(header) => refs_common_mut(&mut header.common, refs),
+            ReferenceOr::Reference { reference, .. } => refs.push(reference),
+        }
+    }
+}
+
+fn refs_schema_mut<'a>(schema: &'a mut ReferenceOr<Schema>, refs: &mut Vec<&'a mut Reference>) {
+    let schema = match schema {
+        ReferenceOr::Item(schema) => schema,
+        ReferenceOr::Reference { reference, .. } => return refs.push(reference),
+    };
+    refs_common_mut(&mut schema.common, refs);
+    for property in schema.properties.values_mut() {
+        refs_schema_mut(property, refs);
+    }
+    if let Some(AdditionalProperties::Schema(additional_properties)) = schema.additional_properties.as_mut() {
+        refs_schema_mut(additional_properties, refs);
+    }
+    for parent in &mut schema.all_of {
+        refs_schema_mut(parent, refs);
+    }
+}
+
+fn refs_common_mut<'a>(common: &'a mut SchemaCommon, refs: &mut Vec<&'a mut Reference>) {
+    if let Some(items) = common.items.as_mut() {
+        refs_schema_mut(items, refs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walks_references_in_the_same_order_as_references_mut() {
+        let mut spec: OpenAPI = serde_json::from_str(
+            r##"{
+                "swagger": "2.0",
+                "info": { "title": "Pets", "version": "1" },
+                "x-ms-parameterized-host": {
+                    "hostTemplate": "{account}.example.com",
+                    "parameters": [{ "$ref": "#/parameters/Account" }]
+                },
+                "paths": {
+                    "/pets": {
+                        "parameters": [{ "$ref": "#/parameters/ApiVersion" }],
+                        "put": {
+                            "parameters": [{ "name": "pet", "in": "body", "schema": { "$ref": "#/definitions/Pet" } }],
+                            "responses": {}
+                        },
+                        "get": {
+                            "parameters": [{ "name": "ids", "in": "query", "type": "array", "items": { "$ref": "#/definitions/Id" } }],
+                            "responses": {
+                                "200": {
+                                    "description": "ok",
+                                    "schema": { "$ref": "#/definitions/Pets" },
+                                    "headers": { "x-next": { "$ref": "#/definitions/Link" } }
+                                }
+                            },
+                            "x-ms-examples": { "list": { "$ref": "./examples/list.json" } }
+                        }
+                    },
+                    "/other": { "$ref": "./other.json#/paths/~1other" }
+                },
+                "x-ms-paths": {
+                    "/pets?op=count": { "head": { "responses": { "200": { "description": "ok", "schema": { "$ref": "#/definitions/Count" } } } } }
+                },
+                "definitions": {
+                    "Pet": {
+                        "properties": {
+                            "id": { "$ref": "#/definitions/Id" },
+                            "tags": { "type": "array", "items": { "$ref": "#/definitions/Tag" } },
+                            "extra": { "additionalProperties": { "$ref": "#/definitions/Extra" } }
+                        },
+                        "allOf": [{ "$ref": "#/definitions/Resource" }]
+                    }
+                },
+                "parameters": {
+                    "Body": { "name": "body", "in": "body", "schema": { "$ref": "#/definitions/Pet" } }
+                },
+                "responses": {
+                    "500": { "description": "error", "schema": { "$ref": "#/definitions/Error" } }
+                }
+            }"##,
+        )
+        .unwrap();
+        let mut walked = Vec::new();
+        spec.walk_references(|pointer, reference| walked.push((pointer.to_owned(), reference.clone())));
+        let references: Vec<Reference> = spec.references_mut().into_iter().map(|reference| reference.clone()).collect();
+        assert_eq!(walked.len(), 15);
+        assert_eq!(
+            walked.iter().map(|(_, reference)| reference.clone()).collect::<Vec<_>>(),
+            references
+        );
+        assert_eq!(walked[1].0, "/paths/~1pets/get/parameters/0/items");
+    }
+}
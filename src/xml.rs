@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Describes how a schema is written as XML.
+/// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#xml-object
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Xml {
+    /// Replaces the name of the element or attribute. On an array, it names the wrapping element, if `wrapped` is true.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The URI of the namespace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// true when a property is written as an attribute instead of an element
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribute: Option<bool>,
+    /// true when the items of an array are wrapped in an element, such as `<books><book/><book/></books>`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrapped: Option<bool>,
+}
@@ -0,0 +1,371 @@
+//! Maps JSON values to and from XML, following the `xml` objects of their schemas
+//! https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md#xml-object
+
+use crate::*;
+use serde_json::{Map, Number, Value};
+use std::fmt;
+use xmltree::{Element, EmitterConfig, Namespace, XMLNode};
+
+/// how deep `allOf` is followed, which guards against schemas that inherit from each other
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug)]
+pub enum XmlError {
+    /// the document is not well-formed XML
+    Parse(xmltree::ParseError),
+    /// the document could not be written
+    Write(xmltree::Error),
+    /// the schema has no `xml.name` and is not a reference to a definition, so the root element has no name
+    Unnamed,
+    /// the reference is not to a definition of the document
+    UnresolvedReference(Reference),
+    /// the root element is not the one the schema describes
+    UnexpectedElement { expected: String, found: String },
+    /// the text of an element or attribute is not of the type of its schema
+    InvalidValue { name: String, text: String },
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlError::Parse(source) => write!(f, "{}", source),
+            XmlError::Write(source) => write!(f, "{}", source),
+            XmlError::Unnamed => write!(f, "the schema has no xml name for the root element"),
+            XmlError::UnresolvedReference(reference) => {
+                let reference = serde_json::to_string(reference).unwrap_or_default();
+                write!(f, "unable to resolve {}", reference)
+            }
+            XmlError::UnexpectedElement { expected, found } => write!(f, "expected the element {}, found {}", expected, found),
+            XmlError::InvalidValue { name, text } => write!(f, "{}: {:?} is not of the type of its schema", name, text),
+        }
+    }
+}
+
+impl std::error::Error for XmlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XmlError::Parse(source) => Some(source),
+            XmlError::Write(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl OpenAPI {
+    /// Writes a value as an XML document described by the schema.
+    /// The root element is named by `xml.name`, or after the definition when the schema is a reference.
+    /// Properties are written as elements, or as attributes when `xml.attribute` is set. Null values are left out.
+    pub fn to_xml(&self, schema: &ReferenceOr<Schema>, value: &Value) -> Result<String, XmlError> {
+        let name = self.root_name(schema)?;
+        let mut elements = self.write_elements(&name, schema, value, true)?;
+        let root = elements.pop().unwrap_or_else(|| Element::new(&name));
+        let mut xml = Vec::new();
+        root.write_with_config(&mut xml, EmitterConfig::new().perform_indent(false))
+            .map_err(XmlError::Write)?;
+        Ok(String::from_utf8(xml).expect("xmltree writes UTF-8"))
+    }
+
+    /// Reads an XML document described by the schema.
+    /// Elements and attributes that the schema does not describe are ignored, unless it has `additionalProperties`.
+    pub fn from_xml(&self, schema: &ReferenceOr<Schema>, xml: &str) -> Result<Value, XmlError> {
+        let root = Element::parse(xml.as_bytes()).map_err(XmlError::Parse)?;
+        let name = self.root_name(schema)?;
+        if root.name != name {
+            return Err(XmlError::UnexpectedElement {
+                expected: name,
+                found: root.name,
+            });
+        }
+        self.read_element(&root, schema)
+    }
+
+    fn root_name(&self, schema: &ReferenceOr<Schema>) -> Result<String, XmlError> {
+        match (xml_name(self.resolve_xml(schema)?), schema) {
+            (Some(name), _) => Ok(name.to_owned()),
+            (None, ReferenceOr::Reference { reference, .. }) => reference.name.clone().ok_or(XmlError::Unnamed),
+            (None, ReferenceOr::Item(_)) => Err(XmlError::Unnamed),
+        }
+    }
+
+    fn resolve_xml<'a>(&'a self, schema: &'a ReferenceOr<Schema>) -> Result<&'a Schema, XmlError> {
+        self.resolve_schema(schema).ok_or_else(|| match schema {
+            ReferenceOr::Reference { reference, .. } => XmlError::UnresolvedReference(reference.clone()),
+            ReferenceOr::Item(_) => unreachable!("items always resolve"),
+        })
+    }
+
+    /// The properties of a schema, including the ones from `allOf`.
+    fn xml_properties<'a>(&'a self, schema: &'a Schema, depth: usize, properties: &mut Vec<(&'a str, &'a ReferenceOr<Schema>)>) {
+        if depth < MAX_DEPTH {
+            for parent in &schema.all_of {
+                if let Some(parent) = self.resolve_schema(parent) {
+                    self.xml_properties(parent, depth + 1, properties);
+                }
+            }
+        }
+        properties.extend(schema.properties.iter().map(|(name, property)| (name.as_str(), property)));
+    }
+
+    /// Writes the elements of a value. An array that is not wrapped is written as an element for each item.
+    fn write_elements(
+        &self,
+        default_name: &str,
+        schema: &ReferenceOr<Schema>,
+        value: &Value,
+        root: bool,
+    ) -> Result<Vec<Element>, XmlError> {
+        let resolved = self.resolve_xml(schema)?;
+        let name = xml_name(resolved).unwrap_or(default_name);
+        match value {
+            Value::Null => Ok(Vec::new()),
+            Value::Array(items) => {
+                let any = ReferenceOr::Item(Schema::default());
+                let item_schema = resolved.common.items.as_ref().as_ref().unwrap_or(&any);
+                let mut elements = Vec::new();
+                for item in items {
+                    elements.extend(self.write_elements(default_name, item_schema, item, false)?);
+                }
+                if root || resolved.xml.as_ref().and_then(|xml| xml.wrapped).unwrap_or(false) {
+                    let mut wrapper = new_element(name, resolved.xml.as_ref());
+                    wrapper.children.extend(elements.into_iter().map(XMLNode::Element));
+                    Ok(vec![wrapper])
+                } else {
+                    Ok(elements)
+                }
+            }
+            Value::Object(map) => {
+                let mut element = new_element(name, resolved.xml.as_ref());
+                let mut properties = Vec::new();
+                self.xml_properties(resolved, 0, &mut properties);
+                for (key, property) in &properties {
+                    if let Some(value) = map.get(*key) {
+                        self.write_property(&mut element, key, property, value)?;
+                    }
+                }
+                let any = ReferenceOr::Item(Schema::default());
+                let additional = match resolved.additional_properties.as_ref() {
+                    Some(AdditionalProperties::Schema(schema)) => schema,
+                    _ => &any,
+                };
+                for (key, value) in map {
+                    if !properties.iter().any(|(name, _)| name == key) {
+                        self.write_property(&mut element, key, additional, value)?;
+                    }
+                }
+                Ok(vec![element])
+            }
+            scalar => {
+                let mut element = new_element(name, resolved.xml.as_ref());
+                element.children.push(XMLNode::Text(text(scalar)));
+                Ok(vec![element])
+            }
+        }
+    }
+
+    fn write_property(&self, element: &mut Element, key: &str, schema: &ReferenceOr<Schema>, value: &Value) -> Result<(), XmlError> {
+        let resolved = self.resolve_xml(schema)?;
+        if is_attribute(resolved) {
+            if !value.is_null() {
+                let name = xml_name(resolved).unwrap_or(key);
+                element.attributes.insert(name.to_owned(), text(value));
+            }
+        } else {
+            let children = self.write_elements(key, schema, value, false)?;
+            element.children.extend(children.into_iter().map(XMLNode::Element));
+        }
+        Ok(())
+    }
+
+    fn read_element(&self, element: &Element, schema: &ReferenceOr<Schema>) -> Result<Value, XmlError> {
+        let resolved = self.resolve_xml(schema)?;
+        let any = ReferenceOr::Item(Schema::default());
+        if is_array(resolved) {
+            let item_schema = resolved.common.items.as_ref().as_ref().unwrap_or(&any);
+            return child_elements(element)
+                .map(|child| self.read_element(child, item_schema))
+                .collect::<Result<_, _>>()
+                .map(Value::Array);
+        }
+        if !is_object(resolved) {
+            return scalar(resolved, &element.name, &element.get_text().unwrap_or_default());
+        }
+
+        let mut map = Map::new();
+        let mut properties = Vec::new();
+        self.xml_properties(resolved, 0, &mut properties);
+        let mut claimed = Vec::new();
+        for (key, property) in properties {
+            let property_schema = self.resolve_xml(property)?;
+            let name = xml_name(property_schema).unwrap_or(key);
+            claimed.push(name);
+            if is_attribute(property_schema) {
+                if let Some(text) = element.attributes.get(name) {
+                    map.insert(key.to_owned(), scalar(property_schema, name, text)?);
+                }
+            } else if is_array(property_schema) && !property_schema.xml.as_ref().and_then(|xml| xml.wrapped).unwrap_or(false) {
+                let item_schema = property_schema.common.items.as_ref().as_ref().unwrap_or(&any);
+                let item_name = xml_name(self.resolve_xml(item_schema)?).unwrap_or(key);
+                claimed.push(item_name);
+                let items = child_elements(element)
+                    .filter(|child| child.name == item_name)
+                    .map(|child| self.read_element(child, item_schema))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if !items.is_empty() {
+                    map.insert(key.to_owned(), Value::Array(items));
+                }
+            } else if let Some(child) = child_elements(element).find(|child| child.name == name) {
+                map.insert(key.to_owned(), self.read_element(child, property)?);
+            }
+        }
+        let additional = match resolved.additional_properties.as_ref() {
+            Some(AdditionalProperties::Schema(schema)) => Some(schema),
+            Some(AdditionalProperties::Boolean(true)) => Some(&any),
+            _ => None,
+        };
+        if let Some(additional) = additional {
+            for child in child_elements(element) {
+                if !claimed.contains(&child.name.as_str()) {
+                    map.insert(child.name.clone(), self.read_element(child, additional)?);
+                }
+            }
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+fn xml_name(schema: &Schema) -> Option<&str> {
+    schema.xml.as_ref().and_then(|xml| xml.name.as_deref())
+}
+
+fn is_attribute(schema: &Schema) -> bool {
+    schema.xml.as_ref().and_then(|xml| xml.attribute).unwrap_or(false)
+}
+
+fn is_array(schema: &Schema) -> bool {
+    schema.common.type_ == Some(DataType::Array) || schema.common.items.is_some()
+}
+
+fn is_object(schema: &Schema) -> bool {
+    schema.common.type_ == Some(DataType::Object)
+        || !schema.properties.is_empty()
+        || !schema.all_of.is_empty()
+        || schema.additional_properties.is_some()
+}
+
+fn new_element(name: &str, xml: Option<&Xml>) -> Element {
+    let mut element = Element::new(name);
+    if let Some(xml) = xml {
+        element.prefix = xml.prefix.clone();
+        if let Some(uri) = &xml.namespace {
+            let mut namespaces = Namespace::empty();
+            namespaces.put(xml.prefix.as_deref().unwrap_or(""), uri.as_str());
+            element.namespace = Some(uri.clone());
+            element.namespaces = Some(namespaces);
+        }
+    }
+    element
+}
+
+fn child_elements(element: &Element) -> impl Iterator<Item = &Element> {
+    element.children.iter().filter_map(XMLNode::as_element)
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Converts text to the type of the schema. Text without a type is kept as a string.
+fn scalar(schema: &Schema, name: &str, text: &str) -> Result<Value, XmlError> {
+    let invalid = || XmlError::InvalidValue {
+        name: name.to_owned(),
+        text: text.to_owned(),
+    };
+    let trimmed = text.trim();
+    match schema.common.type_ {
+        Some(DataType::Integer) => trimmed
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| trimmed.parse::<u64>().map(Value::from))
+            .map_err(|_| invalid()),
+        Some(DataType::Number) => trimmed
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(invalid),
+        Some(DataType::Boolean) => match trimmed {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err(invalid()),
+        },
+        _ => Ok(Value::String(text.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_values_to_and_from_xml() {
+        let spec: OpenAPI = serde_json::from_str(
+            r##"{
+                "swagger": "2.0",
+                "info": {},
+                "paths": {},
+                "definitions": {
+                    "ListBlobsResponse": {
+                        "type": "object",
+                        "xml": { "name": "EnumerationResults" },
+                        "properties": {
+                            "ContainerName": { "type": "string", "xml": { "attribute": true } },
+                            "MaxResults": { "type": "integer" },
+                            "Blobs": {
+                                "type": "array",
+                                "xml": { "wrapped": true },
+                                "items": { "$ref": "#/definitions/BlobItem" }
+                            },
+                            "Tags": { "type": "array", "items": { "type": "string", "xml": { "name": "Tag" } } }
+                        }
+                    },
+                    "BlobItem": {
+                        "type": "object",
+                        "xml": { "name": "Blob" },
+                        "properties": {
+                            "Name": { "type": "string" },
+                            "Deleted": { "type": "boolean" },
+                            "Metadata": { "type": "object", "additionalProperties": { "type": "string" } }
+                        }
+                    }
+                }
+            }"##,
+        )
+        .unwrap();
+        let schema = Schema::reference("ListBlobsResponse");
+        let value = json!({
+            "ContainerName": "pets",
+            "MaxResults": 2,
+            "Blobs": [
+                { "Name": "cat.png", "Deleted": false, "Metadata": { "owner": "me" } },
+                { "Name": "dog & bird.png" }
+            ],
+            "Tags": ["a", "b"]
+        });
+        let xml = spec.to_xml(&schema, &value).unwrap();
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8"?><EnumerationResults ContainerName="pets"><MaxResults>2</MaxResults><Blobs><Blob><Name>cat.png</Name><Deleted>false</Deleted><Metadata><owner>me</owner></Metadata></Blob><Blob><Name>dog &amp; bird.png</Name></Blob></Blobs><Tag>a</Tag><Tag>b</Tag></EnumerationResults>"#
+        );
+        assert_eq!(spec.from_xml(&schema, &xml).unwrap(), value);
+
+        assert!(matches!(spec.from_xml(&schema, "<Blob/>"), Err(XmlError::UnexpectedElement { .. })));
+        assert!(matches!(
+            spec.from_xml(&schema, "<EnumerationResults><MaxResults>many</MaxResults></EnumerationResults>"),
+            Err(XmlError::InvalidValue { .. })
+        ));
+    }
+}
@@ -21,3 +21,37 @@ const PATHS: &[&str] = &[
 fn can_roundtrip_azure_rest_api_specs() -> Result<()> {
     assert_roundtrip_eq(PATHS)
 }
+
+#[cfg(feature = "yaml")]
+#[test]
+fn can_roundtrip_readme_tag() -> Result<()> {
+    use autorust_openapi::Readme;
+    let dir = "../azure-rest-api-specs/specification/vmware/resource-manager";
+    let readme = Readme::read(format!("{}/readme.md", dir))?;
+    let config = readme.tag("package-2020-03");
+    assert_eq!(config.input_files, vec!["Microsoft.AVS/stable/2020-03-20/vmware.json"]);
+    assert_roundtrip_eq(&config.input_paths(dir))
+}
+
+#[test]
+fn can_catalog_azure_rest_api_specs() -> Result<()> {
+    let catalog = autorust_openapi::catalog("../azure-rest-api-specs")?;
+    for failure in &catalog.failures {
+        println!("  failed {}", failure);
+    }
+    println!("  read {} documents, {} failed", catalog.entries.len(), catalog.failures.len());
+    assert!(!catalog.entries.is_empty());
+    Ok(())
+}
+
+#[test]
+fn can_load_azure_rest_api_specs_in_parallel() -> Result<()> {
+    use autorust_openapi::{spec_files, DocumentCache};
+    let paths: Vec<_> = spec_files("../azure-rest-api-specs")?.into_iter().map(|(path, _)| path).collect();
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let cache = DocumentCache::new();
+    let failures = cache.load_all(&paths, threads).into_iter().filter(|result| result.is_err()).count();
+    println!("  read {} documents, {} failed", cache.len(), failures);
+    assert!(cache.len() >= paths.len() - failures);
+    Ok(())
+}
@@ -0,0 +1,25 @@
+// cargo test --test bench_fixtures
+// Checks that the benchmark fixtures stay valid and round trip.
+
+mod common;
+use autorust_openapi::OpenAPI;
+use common::*;
+
+const PATHS: &[&str] = &["benches/fixtures/small.json", "benches/fixtures/medium.json"];
+
+/// Vendored as published. The empty `parameters` arrays of the petstore are not written back, so it does not round trip.
+const VENDORED: &[&str] = &["benches/fixtures/petstore.json"];
+
+#[test]
+fn can_roundtrip_bench_fixtures() -> Result<()> {
+    assert_roundtrip_eq(PATHS)
+}
+
+#[test]
+fn bench_fixtures_are_valid() -> Result<()> {
+    for path in PATHS.iter().chain(VENDORED) {
+        let spec: OpenAPI = serde_json::from_slice(&std::fs::read(path)?)?;
+        assert!(spec.validate().is_empty(), "{} is not valid", path);
+    }
+    Ok(())
+}
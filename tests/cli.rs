@@ -0,0 +1,122 @@
+// cargo test --test cli --features cli
+// Runs the `autorust-openapi` binary and checks that it exits with 0 on success, 1 when a check finds problems and 2 on errors.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const VALID: &str = r##"{
+  "swagger": "2.0",
+  "info": {
+    "title": "Pets",
+    "version": "1"
+  },
+  "paths": {
+    "/pets": {
+      "get": {
+        "operationId": "Pets_List",
+        "responses": {
+          "200": {
+            "description": "ok",
+            "schema": {
+              "$ref": "#/definitions/Pet"
+            }
+          }
+        }
+      }
+    }
+  },
+  "definitions": {
+    "Pet": {
+      "type": "object"
+    }
+  }
+}
+"##;
+
+/// A temporary directory for the files of one test.
+fn dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("autorust-openapi-cli-{}-{}", test, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn exit_code(args: &[&dyn AsRef<std::ffi::OsStr>]) -> i32 {
+    let output = Command::new(env!("CARGO_BIN_EXE_autorust-openapi"))
+        .args(args.iter().map(|arg| arg.as_ref()))
+        .output()
+        .unwrap();
+    output.status.code().unwrap()
+}
+
+#[test]
+fn validate_exit_codes() {
+    let dir = dir("validate");
+    let valid = write(&dir, "valid.json", VALID);
+    let invalid = write(&dir, "invalid.json", &VALID.replace("#/definitions/Pet", "#/definitions/Missing"));
+    assert_eq!(exit_code(&[&"validate", &valid]), 0);
+    assert_eq!(exit_code(&[&"validate", &valid, &invalid]), 1);
+    assert_eq!(exit_code(&[&"validate"]), 2);
+}
+
+#[test]
+fn diff_exit_codes() {
+    let dir = dir("diff");
+    let old = write(&dir, "old.json", VALID);
+    let new = write(&dir, "new.json", &VALID.replace("\"Pet\": {", "\"Animal\": {"));
+    assert_eq!(exit_code(&[&"diff", &old, &old]), 0);
+    assert_eq!(exit_code(&[&"diff", &old, &new]), 1);
+    assert_eq!(exit_code(&[&"diff", &old, &dir.join("missing.json")]), 2);
+}
+
+#[test]
+fn fmt_check_exit_codes() {
+    let dir = dir("fmt");
+    let formatted = write(&dir, "formatted.json", VALID);
+    let unformatted = write(
+        &dir,
+        "unformatted.json",
+        r#"{"info":{"version":"1","title":"Pets"},"swagger":"2.0","paths":{}}"#,
+    );
+    assert_eq!(exit_code(&[&"fmt", &"--check", &formatted]), 0);
+    assert_eq!(exit_code(&[&"fmt", &"--check", &formatted, &unformatted]), 1);
+    assert_eq!(exit_code(&[&"fmt", &"--check", &dir.join("missing.json")]), 2);
+    // checking leaves the file as it was
+    assert!(fs::read_to_string(&unformatted).unwrap().starts_with(r#"{"info""#));
+}
+
+#[test]
+fn unused_fix_keeps_the_rest_of_the_file() {
+    let dir = dir("unused");
+    // indented with four spaces, with keys out of the canonical order, so that formatting would change them
+    let spec = r##"{
+    "info": { "version": "1", "title": "Pets" },
+    "swagger": "2.0",
+    "paths": {
+        "/pets": { "get": { "responses": { "200": { "description": "ok", "schema": { "$ref": "#/definitions/Pet" } } } } }
+    },
+    "definitions": {
+        "Unused": { "type": "object" },
+        "Pet": { "type": "object", "properties": { "name": { "type": "string", "description": "café" } } },
+        "Other": { "type": "string" }
+    },
+    "x-custom": [1, 2.50]
+}
+"##;
+    let file = write(&dir, "pets.json", spec);
+    assert_eq!(exit_code(&[&"unused", &file]), 1);
+    assert_eq!(exit_code(&[&"unused", &"--fix", &file]), 0);
+    let expected = spec
+        .replace("        \"Unused\": { \"type\": \"object\" },\n", "")
+        .replace(",\n        \"Other\": { \"type\": \"string\" }", "");
+    assert_eq!(fs::read_to_string(&file).unwrap(), expected);
+    assert_eq!(exit_code(&[&"unused", &file]), 0);
+}
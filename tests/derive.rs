@@ -0,0 +1,151 @@
+// cargo test --test derive --features derive
+// Checks the schemas of `#[derive(OpenApiSchema)]` against how serde serializes the types.
+
+use autorust_openapi::{OpenAPI, OpenApiSchema};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// A pet in the store.
+#[derive(Serialize, OpenApiSchema)]
+#[serde(rename_all = "camelCase")]
+struct Pet {
+    /// The unique identifier.
+    pet_id: String,
+    #[serde(rename = "displayName")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<Tag>,
+    #[serde(skip)]
+    #[allow(dead_code)]
+    etag: String,
+    #[serde(flatten)]
+    audit: Audit,
+    kind: PetKind,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct Tag(String);
+
+#[derive(Serialize, OpenApiSchema)]
+#[serde(rename_all = "camelCase")]
+struct Audit {
+    created_by: String,
+    labels: HashMap<String, u32>,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+#[serde(rename_all = "snake_case")]
+enum PetKind {
+    /// Barks.
+    HouseDog,
+    Cat,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+#[serde(tag = "shape")]
+enum Shape {
+    #[serde(rename = "circle")]
+    Circle {
+        radius: f64,
+    },
+    /// A square.
+    Square(Square),
+    Empty,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct Square {
+    side: f64,
+}
+
+#[test]
+fn derives_struct_schemas() {
+    let spec = OpenAPI::new("Pets", "1").definitions_of::<Pet>();
+    assert_eq!(
+        serde_json::to_value(&spec.definitions).unwrap(),
+        json!({
+            "Pet": {
+                "type": "object",
+                "description": "A pet in the store.",
+                "required": ["petId", "createdBy", "labels", "kind"],
+                "properties": {
+                    "petId": { "type": "string", "description": "The unique identifier." },
+                    "displayName": { "type": "string", "x-nullable": true },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "createdBy": { "type": "string" },
+                    "labels": { "type": "object", "additionalProperties": { "type": "integer", "format": "int64" } },
+                    "kind": { "$ref": "#/definitions/PetKind" }
+                }
+            },
+            "PetKind": {
+                "type": "string",
+                "enum": ["house_dog", "cat"],
+                "x-ms-enum": {
+                    "name": "PetKind",
+                    "modelAsString": false,
+                    "values": [{ "value": "house_dog", "description": "Barks." }, { "value": "cat" }]
+                }
+            }
+        })
+    );
+    let kinds = [PetKind::HouseDog, PetKind::Cat];
+    assert_eq!(serde_json::to_value(kinds).unwrap(), json!(["house_dog", "cat"]));
+}
+
+#[test]
+fn derives_polymorphic_schemas() {
+    let spec = OpenAPI::new("Shapes", "1").definitions_of::<Shape>();
+    assert_eq!(
+        serde_json::to_value(&spec.definitions).unwrap(),
+        json!({
+            "Shape": {
+                "type": "object",
+                "required": ["shape"],
+                "properties": { "shape": { "type": "string" } },
+                "discriminator": "shape"
+            },
+            "ShapeCircle": {
+                "type": "object",
+                "required": ["radius"],
+                "properties": { "radius": { "type": "number", "format": "double" } },
+                "allOf": [{ "$ref": "#/definitions/Shape" }],
+                "x-ms-discriminator-value": "circle"
+            },
+            "ShapeSquare": {
+                "type": "object",
+                "description": "A square.",
+                "allOf": [{ "$ref": "#/definitions/Shape" }, { "$ref": "#/definitions/Square" }],
+                "x-ms-discriminator-value": "Square"
+            },
+            "Square": {
+                "type": "object",
+                "required": ["side"],
+                "properties": { "side": { "type": "number", "format": "double" } }
+            },
+            "ShapeEmpty": {
+                "type": "object",
+                "allOf": [{ "$ref": "#/definitions/Shape" }],
+                "x-ms-discriminator-value": "Empty"
+            }
+        })
+    );
+    let shapes = [Shape::Circle { radius: 1.0 }, Shape::Square(Square { side: 2.0 }), Shape::Empty];
+    assert_eq!(
+        serde_json::to_value(shapes).unwrap(),
+        json!([{ "shape": "circle", "radius": 1.0 }, { "shape": "Square", "side": 2.0 }, { "shape": "Empty" }])
+    );
+    assert!(spec.validate().is_empty());
+}
+
+/// Takes the name of the definition of `Shape::Empty`.
+#[derive(Serialize, OpenApiSchema)]
+struct ShapeEmpty {
+    size: u32,
+}
+
+#[test]
+#[should_panic(expected = "the definition ShapeEmpty of Shape::Empty is already used by another type")]
+fn fails_on_definition_collisions() {
+    OpenAPI::new("Shapes", "1").definitions_of::<ShapeEmpty>().definitions_of::<Shape>();
+}